@@ -2,7 +2,7 @@ use std::fs::read_to_string;
 use divan::{black_box, Bencher};
 use advent_of_code::{
     default_input_file,
-    puzzles::year2023::day17::{part1, part2}
+    puzzles::year2023::day17::{part1, part2, part1_with, part2_with, Method}
 };
 
 #[divan::bench]
@@ -17,4 +17,32 @@ fn bench2(bencher: Bencher) {
     let file = default_input_file("./src/puzzles", 2023, 17, 2);
     let input = read_to_string(file).unwrap();
     bencher.bench_local( move || { let _ = black_box(part2(&input)); } );
-}
\ No newline at end of file
+}
+
+#[divan::bench]
+fn bench1_hashed(bencher: Bencher) {
+    let file = default_input_file("./src/puzzles", 2023, 17, 1);
+    let input = read_to_string(file).unwrap();
+    bencher.bench_local( move || { let _ = black_box(part1_with(&input, Method::AStarHashed)); } );
+}
+
+#[divan::bench]
+fn bench2_hashed(bencher: Bencher) {
+    let file = default_input_file("./src/puzzles", 2023, 17, 2);
+    let input = read_to_string(file).unwrap();
+    bencher.bench_local( move || { let _ = black_box(part2_with(&input, Method::AStarHashed)); } );
+}
+
+#[divan::bench]
+fn bench1_dial(bencher: Bencher) {
+    let file = default_input_file("./src/puzzles", 2023, 17, 1);
+    let input = read_to_string(file).unwrap();
+    bencher.bench_local( move || { let _ = black_box(part1_with(&input, Method::Dial)); } );
+}
+
+#[divan::bench]
+fn bench2_dial(bencher: Bencher) {
+    let file = default_input_file("./src/puzzles", 2023, 17, 2);
+    let input = read_to_string(file).unwrap();
+    bencher.bench_local( move || { let _ = black_box(part2_with(&input, Method::Dial)); } );
+}
@@ -0,0 +1,430 @@
+use std::ops::{Index, BitAnd, BitOr, BitXor, Not, BitAndAssign, BitOrAssign, BitXorAssign};
+use nom::{
+    IResult,
+    character::complete::{one_of, line_ending},
+    sequence::terminated,
+    combinator::{opt, verify},
+    multi::many1
+};
+
+type Pos = [usize; 2];
+
+const WORD: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// mirror line between two rows
+    Row,
+    /// mirror line between two columns
+    Column
+}
+
+/// Boolean matrix of any size, each row is packed into `u64` words (column `x` is bit `x % 64` of word `x / 64`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>
+}
+impl BitMatrix {
+    #[inline]
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(WORD);
+        Self { width, height, stride, words: vec![0; stride * height] }
+    }
+    #[inline]
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> bool) -> Self {
+        let mut result = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if f(x, y) {
+                    result.set(x, y, true);
+                }
+            }
+        }
+        result
+    }
+    /// rows of `#` (set) and `.` (unset), each optionally followed by a line ending
+    ///
+    /// fails if the rows differ in length
+    pub fn parse(input: &str) -> IResult<&str, Self> {
+        let (input, rows) = verify(
+            many1(terminated(many1(one_of(".#")), opt(line_ending))),
+            |rows: &Vec<Vec<char>>| rows.iter().all( |row| row.len() == rows[0].len() )
+        )(input)?;
+        Ok((input, Self::from_fn(rows[0].len(), rows.len(), |x, y| rows[y][x] == '#' )))
+    }
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        debug_assert!(x < self.width && y < self.height);
+        self.words[y * self.stride + x / WORD] >> (x % WORD) & 1 != 0
+    }
+    #[inline(always)]
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        debug_assert!(x < self.width && y < self.height);
+        let word = &mut self.words[y * self.stride + x / WORD];
+        let mask = 1 << (x % WORD);
+        if value { *word |= mask } else { *word &= !mask }
+    }
+    /// packed words of row `y`, bits beyond the width are always zero
+    #[inline(always)]
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+    #[inline(always)]
+    pub fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.stride..(y + 1) * self.stride]
+    }
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map( |w| w.count_ones() as usize ).sum()
+    }
+    /// clears the bits beyond the width in the last word of every row
+    #[inline]
+    fn mask_tail(&mut self) {
+        if self.width % WORD == 0 { return; }
+        let mask = (1 << (self.width % WORD)) - 1;
+        for y in 0..self.height {
+            self.words[(y + 1) * self.stride - 1] &= mask;
+        }
+    }
+    /// number of columns in which rows `a` and `b` differ
+    #[inline]
+    pub fn row_distance(&self, a: usize, b: usize) -> u32 {
+        self.row(a).iter().zip(self.row(b))
+            .map( |(a, b)| (a ^ b).count_ones() )
+            .sum()
+    }
+    /// number of rows in which columns `a` and `b` differ
+    #[inline]
+    pub fn column_distance(&self, a: usize, b: usize) -> u32 {
+        (0..self.height)
+            .filter( |y| self.get(a, *y) != self.get(b, *y) )
+            .count() as u32
+    }
+    /// transposes 64x64 blocks in place with word-wide swaps
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::new(self.height, self.width);
+        let mut block = [0u64; WORD];
+        for by in 0..self.height.div_ceil(WORD) {
+            for bx in 0..self.stride {
+                for (i, word) in block.iter_mut().enumerate() {
+                    let y = by * WORD + i;
+                    *word = if y < self.height { self.words[y * self.stride + bx] } else { 0 };
+                }
+                transpose64(&mut block);
+                for (i, word) in block.iter().enumerate() {
+                    let x = bx * WORD + i;
+                    if x < self.width {
+                        result.words[x * result.stride + by] = *word;
+                    }
+                }
+            }
+        }
+        result
+    }
+    /// position of a mirror line along `axis` with exactly `errors` mismatched cells
+    ///
+    /// The result is the number of rows above or columns left of the line.
+    pub fn broken_mirror_axis(&self, axis: Axis, errors: u32) -> Option<usize> {
+        // columns are compared as rows of the transpose, a whole word at a time
+        if axis == Axis::Column { return self.transpose().broken_mirror_axis(Axis::Row, errors); }
+        let len = self.height;
+        'outer: for axis in 1..len {
+            let mut total = 0;
+            for (i, j) in (0..axis).rev().zip(axis..len) {
+                total += self.row_distance(i, j);
+                if total > errors {
+                    continue 'outer;
+                }
+            }
+            if total == errors {
+                return Some(axis);
+            }
+        }
+        None
+    }
+    #[inline]
+    pub fn mirror_axis(&self, axis: Axis) -> Option<usize> {
+        self.broken_mirror_axis(axis, 0)
+    }
+}
+
+/// Dense set of grid positions, indexed `[x, y]` like the `ndarray` grids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid(BitMatrix);
+impl BitGrid {
+    #[inline]
+    pub fn new((w, h): (usize, usize)) -> Self {
+        Self(BitMatrix::new(w, h))
+    }
+    #[inline]
+    pub fn from_fn((w, h): (usize, usize), mut f: impl FnMut(Pos) -> bool) -> Self {
+        Self(BitMatrix::from_fn(w, h, |x, y| f([x, y]) ))
+    }
+    #[inline(always)]
+    pub fn dim(&self) -> (usize, usize) {
+        (self.0.width, self.0.height)
+    }
+    #[inline(always)]
+    pub fn matrix(&self) -> &BitMatrix {
+        &self.0
+    }
+    /// `false` outside of the grid
+    #[inline(always)]
+    pub fn contains(&self, [x, y]: Pos) -> bool {
+        x < self.0.width && y < self.0.height && self.0.get(x, y)
+    }
+    /// returns whether `pos` was newly inserted, panics outside of the grid
+    #[inline(always)]
+    pub fn insert(&mut self, [x, y]: Pos) -> bool {
+        assert!(x < self.0.width && y < self.0.height, "position out of bounds");
+        let new = !self.0.get(x, y);
+        self.0.set(x, y, true);
+        new
+    }
+    /// returns whether `pos` was present, panics outside of the grid
+    #[inline(always)]
+    pub fn remove(&mut self, [x, y]: Pos) -> bool {
+        assert!(x < self.0.width && y < self.0.height, "position out of bounds");
+        let old = self.0.get(x, y);
+        self.0.set(x, y, false);
+        old
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.words.fill(0);
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.words.iter().all( |w| *w == 0 )
+    }
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.0.count_ones()
+    }
+    #[inline]
+    pub fn row_count(&self, y: usize) -> usize {
+        self.0.row(y).iter().map( |w| w.count_ones() as usize ).sum()
+    }
+    #[inline]
+    pub fn column_count(&self, x: usize) -> usize {
+        (0..self.0.height).filter( |y| self.0.get(x, *y) ).count()
+    }
+    /// all positions in the set, row by row
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Pos> + '_ {
+        let stride = self.0.stride;
+        self.0.words.iter().enumerate()
+            .filter( |(_, word)| **word != 0 )
+            .flat_map( move |(i, &word)| {
+                let (y, x0) = (i / stride, i % stride * WORD);
+                (0..WORD)
+                    .filter( move |bit| word >> bit & 1 != 0 )
+                    .map( move |bit| [x0 + bit, y] )
+            } )
+    }
+    /// every position moved by `[dx, dy]`, positions leaving the grid are dropped
+    pub fn shift(&self, [dx, dy]: [isize; 2]) -> Self {
+        let (w, h) = self.dim();
+        let mut result = Self::new((w, h));
+        let (q, r) = (dx.unsigned_abs() / WORD, (dx.unsigned_abs() % WORD) as u32);
+        for y in 0..h {
+            let Some(source) = y.checked_add_signed(-dy).filter( |source| *source < h ) else { continue };
+            let src = self.0.row(source);
+            let dst = result.0.row_mut(y);
+            for (i, word) in dst.iter_mut().enumerate() {
+                // `get(j)` is the source word `j`, or zero beyond the row
+                let get = |j: Option<usize>| j.and_then( |j| src.get(j) ).copied().unwrap_or(0);
+                *word = if dx >= 0 {
+                    let (a, b) = (i.checked_sub(q), i.checked_sub(q + 1));
+                    if r == 0 { get(a) } else { get(a) << r | get(b) >> (u64::BITS - r) }
+                } else {
+                    let (a, b) = (Some(i + q), Some(i + q + 1));
+                    if r == 0 { get(a) } else { get(a) >> r | get(b) << (u64::BITS - r) }
+                };
+            }
+        }
+        result.0.mask_tail();
+        result
+    }
+    /// compact copy of the set, equal exactly for equal grids of the same size
+    #[inline]
+    pub fn fingerprint(&self) -> Box<[u64]> {
+        self.0.words.clone().into_boxed_slice()
+    }
+    #[inline]
+    fn zip_with(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(self.dim(), other.dim());
+        for (a, b) in self.0.words.iter_mut().zip(&other.0.words) {
+            *a = f(*a, *b);
+        }
+    }
+}
+impl From<BitMatrix> for BitGrid {
+    #[inline]
+    fn from(matrix: BitMatrix) -> Self {
+        Self(matrix)
+    }
+}
+impl Index<Pos> for BitGrid {
+    type Output = bool;
+    #[inline(always)]
+    fn index(&self, [x, y]: Pos) -> &bool {
+        assert!(x < self.0.width && y < self.0.height, "position out of bounds");
+        if self.0.get(x, y) { &true } else { &false }
+    }
+}
+impl Not for &BitGrid {
+    type Output = BitGrid;
+    #[inline]
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        result.0.words.iter_mut().for_each( |w| *w = !*w );
+        result.0.mask_tail();
+        result
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $f:expr) => {
+        impl $op_assign<&BitGrid> for BitGrid {
+            #[inline]
+            fn $fn_assign(&mut self, other: &BitGrid) {
+                self.zip_with(other, $f);
+            }
+        }
+        impl $op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+            #[inline]
+            fn $fn(self, other: &BitGrid) -> BitGrid {
+                let mut result = self.clone();
+                result.zip_with(other, $f);
+                result
+            }
+        }
+    };
+}
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b );
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b );
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b );
+
+/// transposes a 64x64 bit block, `block[r] >> c & 1` becomes `block[c] >> r & 1`
+#[inline]
+fn transpose64(block: &mut [u64; WORD]) {
+    let mut j = WORD / 2;
+    let mut mask = u64::MAX >> j;
+    while j != 0 {
+        let mut k = 0;
+        while k < WORD {
+            let t = ((block[k] >> j) ^ block[k + j]) & mask;
+            block[k] ^= t << j;
+            block[k + j] ^= t;
+            k = (k + j + 1) & !j;
+        }
+        j >>= 1;
+        mask ^= mask << j;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transpose() {
+        for (w, h) in [(1, 1), (9, 7), (64, 64), (70, 130), (200, 3)] {
+            let matrix = BitMatrix::from_fn(w, h, |x, y| (x * 7 + y * 13) % 5 == 0 || x == y );
+            let transposed = matrix.transpose();
+            assert_eq!((transposed.width(), transposed.height()), (h, w));
+            for y in 0..h {
+                for x in 0..w {
+                    assert_eq!(matrix.get(x, y), transposed.get(y, x));
+                }
+            }
+            assert_eq!(transposed.transpose(), matrix);
+        }
+    }
+
+    #[test]
+    fn mirrors() {
+        let (_, matrix) = BitMatrix::parse("#.##..##.\n..#.##.#.\n##......#\n##......#\n..#.##.#.\n..##..##.\n#.#.##.#.\n")
+            .unwrap();
+        assert_eq!(matrix.mirror_axis(Axis::Column), Some(5));
+        assert_eq!(matrix.mirror_axis(Axis::Row), None);
+        assert_eq!(matrix.broken_mirror_axis(Axis::Row, 1), Some(3));
+        assert_eq!(matrix.transpose().mirror_axis(Axis::Row), Some(5));
+        assert_eq!(matrix.row_distance(0, 5), 1);
+        assert_eq!(matrix.column_distance(4, 5), 0);
+        // wider than any integer type
+        let symmetric = BitMatrix::from_fn(150, 4, |x, y| (x.min(149 - x) + y) % 3 == 0 );
+        let mut broken = symmetric.clone();
+        broken.set(140, 2, !broken.get(140, 2));
+        assert_eq!(broken.mirror_axis(Axis::Column), None);
+        assert_eq!(broken.broken_mirror_axis(Axis::Column, 1), Some(75));
+        assert_eq!(symmetric.mirror_axis(Axis::Column), Some(75));
+    }
+
+    #[test]
+    fn grid() {
+        let (w, h) = (70, 5);
+        let pattern = |[x, y]: Pos| (x + 2 * y) % 7 == 0 || x == 69 ;
+        let grid = BitGrid::from_fn((w, h), pattern);
+        assert_eq!(grid.count(), (0..w).flat_map( |x| (0..h).map( move |y| [x, y] ) ).filter( |p| pattern(*p) ).count());
+        assert_eq!(grid.iter().count(), grid.count());
+        assert!(grid.iter().all( |p| grid[p] ));
+        assert_eq!(grid.row_count(0), 11);
+        assert_eq!(grid.column_count(69), 5);
+        assert!(!grid.contains([70, 0]));
+        for offset in [[1, 0], [-1, 0], [0, 1], [0, -1], [65, 2], [-64, -1]] {
+            let shifted = grid.shift(offset);
+            for x in 0..w {
+                for y in 0..h {
+                    let source = [x as isize - offset[0], y as isize - offset[1]];
+                    let expected = source.iter().all( |c| *c >= 0 )
+                        && grid.contains([source[0] as usize, source[1] as usize]);
+                    assert_eq!(shifted[[x, y]], expected, "{offset:?} at {x}, {y}");
+                }
+            }
+        }
+        let other = grid.shift([1, 1]);
+        assert_eq!((&grid & &other).count() + (&grid | &other).count(), grid.count() + other.count());
+        assert_eq!((&grid ^ &other), &(&grid | &other) & &!&(&grid & &other));
+        assert_eq!((!&grid).count(), w * h - grid.count());
+        let mut copy = grid.clone();
+        assert!(!copy.insert([69, 0]));
+        assert!(copy.remove([69, 0]));
+        assert_ne!(copy.fingerprint(), grid.fingerprint());
+        copy ^= &copy.clone();
+        assert!(copy.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        // still inside the last word of the row
+        BitGrid::new((70, 5)).insert([70, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "position out of bounds")]
+    fn index_out_of_bounds() {
+        let _ = BitGrid::new((70, 5))[[70, 0]];
+    }
+
+    #[test]
+    fn ragged() {
+        assert!(BitMatrix::parse("#.#\n.#\n").is_err());
+        let (rest, matrix) = BitMatrix::parse("#.\n.#\n\n##.").unwrap();
+        assert_eq!((matrix.width(), matrix.height(), rest), (2, 2, "\n##."));
+    }
+}
@@ -0,0 +1,179 @@
+use std::array;
+use itertools::Itertools;
+use num_traits::PrimInt;
+
+/// Sorted distinct coordinates along one axis, each with a weighted position.
+///
+/// The cost of going from one coordinate to the next is given by a weight function,
+/// so empty stretches between them can be shrunk or expanded at will.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis<T> {
+    values: Vec<T>,
+    positions: Vec<u64>
+}
+impl<T: PrimInt> CompressedAxis<T> {
+    /// `weight(a, b)` is the cost from coordinate `a` to the next used coordinate `b`
+    pub fn new(coordinates: impl IntoIterator<Item = T>, mut weight: impl FnMut(T, T) -> u64) -> Self {
+        let values = coordinates.into_iter().sorted_unstable().dedup().collect_vec();
+        let mut positions = Vec::with_capacity(values.len());
+        positions.extend(values.first().map( |_| 0 ));
+        for (a, b) in values.iter().copied().tuple_windows() {
+            positions.push(positions.last().unwrap() + weight(a, b));
+        }
+        Self { values, positions }
+    }
+    /// weighted positions equal to the original coordinates (relative to the smallest one)
+    #[inline]
+    pub fn uniform(coordinates: impl IntoIterator<Item = T>) -> Self {
+        Self::new(coordinates, |a, b| (b - a).to_u64().unwrap() )
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    /// compressed index of `value`, if it is one of the coordinates
+    #[inline]
+    pub fn index(&self, value: T) -> Option<usize> {
+        self.values.binary_search(&value).ok()
+    }
+    /// original coordinate of compressed `index`
+    #[inline(always)]
+    pub fn value(&self, index: usize) -> T {
+        self.values[index]
+    }
+    #[inline(always)]
+    pub fn position(&self, index: usize) -> u64 {
+        self.positions[index]
+    }
+    #[inline]
+    pub fn distance(&self, a: usize, b: usize) -> u64 {
+        self.positions[a].abs_diff(self.positions[b])
+    }
+    /// sum of `distance(a, b)` over all unordered pairs of `indices`, without enumerating the pairs
+    pub fn sum_pairwise(&self, indices: impl IntoIterator<Item = usize>) -> u64 {
+        let mut counts = vec![0u64; self.len()];
+        for i in indices {
+            counts[i] += 1;
+        }
+        // every gap is crossed by each pair with one point on either side
+        let total = counts.iter().sum::<u64>();
+        let mut before = 0;
+        counts.iter().zip(self.positions.iter().tuple_windows())
+            .map( |(count, (a, b))| {
+                before += count;
+                before * (total - before) * (b - a)
+            } )
+            .sum()
+    }
+}
+
+/// Coordinate compression of points in `N` dimensions, one `CompressedAxis` per dimension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression<T, const N: usize>([CompressedAxis<T>; N]);
+impl<T: PrimInt, const N: usize> Compression<T, N> {
+    /// `weight(axis, a, b)` is the cost from coordinate `a` to the next used coordinate `b` along `axis`
+    pub fn new<'a>(points: impl IntoIterator<Item = &'a [T; N]> + Clone, mut weight: impl FnMut(usize, T, T) -> u64) -> Self
+    where T: 'a
+    {
+        Self(array::from_fn( |axis| {
+            CompressedAxis::new(points.clone().into_iter().map( |p| p[axis] ), |a, b| weight(axis, a, b) )
+        } ))
+    }
+    #[inline]
+    pub fn uniform<'a>(points: impl IntoIterator<Item = &'a [T; N]> + Clone) -> Self
+    where T: 'a
+    {
+        Self::new(points, |_, a, b| (b - a).to_u64().unwrap() )
+    }
+    #[inline(always)]
+    pub fn from_axes(axes: [CompressedAxis<T>; N]) -> Self {
+        Self(axes)
+    }
+    #[inline(always)]
+    pub fn axes(&self) -> &[CompressedAxis<T>; N] {
+        &self.0
+    }
+    #[inline(always)]
+    pub fn shape(&self) -> [usize; N] {
+        array::from_fn( |axis| self.0[axis].len() )
+    }
+    /// compressed indices of `point`, if every coordinate is known
+    #[inline]
+    pub fn compress(&self, point: &[T; N]) -> Option<[usize; N]> {
+        let mut result = [0; N];
+        for (index, (axis, value)) in result.iter_mut().zip(self.0.iter().zip(point)) {
+            *index = axis.index(*value)?;
+        }
+        Some(result)
+    }
+    #[inline]
+    pub fn decompress(&self, index: [usize; N]) -> [T; N] {
+        array::from_fn( |axis| self.0[axis].value(index[axis]) )
+    }
+    /// weighted Manhattan distance between compressed points
+    #[inline]
+    pub fn distance(&self, a: [usize; N], b: [usize; N]) -> u64 {
+        (0..N).map( |axis| self.0[axis].distance(a[axis], b[axis]) ).sum()
+    }
+    /// sum of `distance(a, b)` over all unordered pairs of compressed `points`
+    ///
+    /// Takes `O(points + shape)` time instead of visiting every pair.
+    pub fn sum_pairwise(&self, points: &[[usize; N]]) -> u64 {
+        (0..N)
+            .map( |axis| self.0[axis].sum_pairwise(points.iter().map( |p| p[axis] )) )
+            .sum()
+    }
+}
+
+/// Manhattan distance between points with original coordinates
+#[inline]
+pub fn manhattan<T: PrimInt, const N: usize>(a: &[T; N], b: &[T; N]) -> T {
+    a.iter().zip(b)
+        .fold(T::zero(), |acc, (a, b)| acc + if a < b { *b - *a } else { *a - *b } )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn axis() {
+        let axis = CompressedAxis::new([7i32, -3, 7, 2, 100], |a, b| (b - a) as u64 * 10 );
+        assert_eq!(axis.len(), 4);
+        assert_eq!(axis.index(7), Some(2));
+        assert_eq!(axis.index(5), None);
+        assert_eq!(axis.value(3), 100);
+        assert_eq!(axis.position(2), 100);
+        assert_eq!(axis.distance(3, 1), 980);
+        assert_eq!(CompressedAxis::<u8>::uniform([]).sum_pairwise([]), 0);
+    }
+
+    #[test]
+    fn pairwise() {
+        let points = [[3i64, -1, 7], [0, 0, 0], [3, 5, -2], [-8, 5, 7], [3, -1, 7], [10, 2, 4]];
+        let uniform = Compression::uniform(&points);
+        let compressed = points.iter().map( |p| uniform.compress(p).unwrap() ).collect_vec();
+        for (point, index) in points.iter().zip(&compressed) {
+            assert_eq!(&uniform.decompress(*index), point);
+        }
+        assert_eq!(uniform.compress(&[3, 5, 1]), None);
+        let expected = points.iter().tuple_combinations()
+            .map( |(a, b)| manhattan(a, b) as u64 )
+            .sum::<u64>();
+        assert_eq!(uniform.sum_pairwise(&compressed), expected);
+        // empty stretches cost 5 per unit along the first axis only
+        let weighted = Compression::new(&points, |axis, a, b| {
+            let gap = (b - a) as u64;
+            if axis == 0 { 1 + (gap - 1) * 5 } else { gap }
+        } );
+        let expected = compressed.iter().tuple_combinations()
+            .map( |(a, b)| weighted.distance(*a, *b) )
+            .sum::<u64>();
+        assert_eq!(weighted.sum_pairwise(&compressed), expected);
+        assert_eq!(weighted.distance(compressed[1], compressed[5]), 1 + 2 * 5 + 1 + 6 * 5 + 2 + 4);
+    }
+}
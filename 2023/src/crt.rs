@@ -0,0 +1,139 @@
+use num::Integer;
+
+use crate::cycle::reduce;
+
+/// Smallest `x >= 0` with `x = a (mod m)` and `x = b (mod n)`, returned as `(x, lcm(m, n))`.
+///
+/// `None` if there is no solution or `lcm(m, n)` does not fit into `usize`.
+pub fn crt((a, m): (usize, usize), (b, n): (usize, usize)) -> Option<(usize, usize)> {
+    let (a, m, b, n) = (a as i128, m as i128, b as i128, n as i128);
+    let egcd = m.extended_gcd(&n);
+    let (q, r) = (b - a).div_mod_floor(&egcd.gcd);
+    if r != 0 { return None; }
+    // both factors are reduced below n / gcd < 2^64 first, so the products fit into u128
+    let n = n / egcd.gcd;
+    let k = q.mod_floor(&n) as u128 * egcd.x.mod_floor(&n) as u128 % n as u128;
+    let lcm = usize::try_from(m as u128 * n as u128).ok()?;
+    let x = (a as u128 + m as u128 * k) % lcm as u128;
+    Some((x as usize, lcm))
+}
+
+/// Eventually periodic walk: steps `0..prefix` are visited once, after that the walk repeats every `period` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orbit {
+    prefix: usize,
+    period: usize,
+    goals: Vec<usize>
+}
+impl Orbit {
+    /// `goals` are the steps below `prefix + period` at which the walker is at a goal
+    #[inline]
+    pub fn new(prefix: usize, period: usize, mut goals: Vec<usize>) -> Self {
+        assert!(period > 0);
+        goals.sort_unstable();
+        goals.dedup();
+        assert!(goals.iter().all( |g| *g < prefix + period ));
+        Self { prefix, period, goals }
+    }
+    #[inline(always)]
+    pub fn prefix(&self) -> usize {
+        self.prefix
+    }
+    #[inline(always)]
+    pub fn period(&self) -> usize {
+        self.period
+    }
+    #[inline]
+    pub fn is_goal(&self, n: usize) -> bool {
+        self.goals.binary_search(&reduce(n, (self.prefix, self.period))).is_ok()
+    }
+    /// all goal steps below `end`
+    #[inline]
+    fn goals_before(&self, end: usize) -> impl Iterator<Item = usize> + '_ {
+        self.goals.iter()
+            .flat_map( move |&g| {
+                let period = if g < self.prefix { end } else { self.period };
+                (g..end).step_by(period)
+            } )
+    }
+    /// goal steps inside the cycle as residues modulo `period`
+    #[inline]
+    fn residues(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.goals.iter()
+            .filter( |g| **g >= self.prefix )
+            .map( |g| (g % self.period, self.period) )
+    }
+}
+
+/// Earliest step at which every orbit is at one of its goals.
+pub fn synchronise(orbits: &[Orbit]) -> Option<usize> {
+    let Some((first, rest)) = orbits.split_first() else { return Some(0) };
+    // steps before all orbits have entered their cycle are checked directly
+    let prefix = orbits.iter().map(Orbit::prefix).max().unwrap();
+    let early = first.goals_before(prefix)
+        .filter( |n| rest.iter().all( |o| o.is_goal(*n) ) )
+        .min();
+    if early.is_some() { return early; }
+    // afterwards every orbit is a union of residue classes
+    let mut classes = first.residues().collect::<Vec<_>>();
+    for orbit in rest {
+        classes = classes.iter()
+            .flat_map( |a| orbit.residues().filter_map( |b| crt(*a, b) ) )
+            .collect();
+        classes.sort_unstable();
+        classes.dedup();
+    }
+    classes.into_iter()
+        .map( |(x, m)| if x >= prefix { x } else { x + (prefix - x).div_ceil(m) * m } )
+        .min()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pairwise() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((0, 4), (1, 6)), None);
+        assert_eq!(crt((0, 1), (5, 7)), Some((5, 7)));
+    }
+
+    #[test]
+    fn large_moduli() {
+        let (m, n) = (4294967291, 4294967279);
+        let (x, lcm) = crt((m - 1, m), (n - 2, n)).unwrap();
+        assert_eq!(lcm, m * n);
+        assert_eq!((x % m, x % n), (m - 1, n - 2));
+        assert_eq!(crt((1, 1 << 63), (0, 3)), None);
+    }
+
+    #[test]
+    fn orbits() {
+        // day8 style: every walker only reaches its goal at multiples of the period
+        let orbits = [Orbit::new(2, 2, vec![2]), Orbit::new(1, 3, vec![3])];
+        assert_eq!(synchronise(&orbits), Some(6));
+        // goal before the cycle starts
+        let orbits = [Orbit::new(3, 4, vec![1, 5]), Orbit::new(0, 2, vec![1])];
+        assert_eq!(synchronise(&orbits), Some(1));
+        // multiple goals per cycle
+        let orbits = [Orbit::new(0, 5, vec![1, 3]), Orbit::new(2, 7, vec![4, 8])];
+        assert_eq!(synchronise(&orbits), Some(8));
+        // incompatible parities
+        let orbits = [Orbit::new(0, 2, vec![0]), Orbit::new(1, 4, vec![3])];
+        assert_eq!(synchronise(&orbits), None);
+        assert_eq!(synchronise(&[]), Some(0));
+    }
+
+    #[test]
+    fn brute_force() {
+        let orbits = [
+            Orbit::new(4, 6, vec![2, 5, 9]),
+            Orbit::new(1, 10, vec![0, 3, 7]),
+            Orbit::new(6, 4, vec![7])
+        ];
+        let expected = (0..1000).find( |n| orbits.iter().all( |o| o.is_goal(*n) ) );
+        assert_eq!(synchronise(&orbits), expected);
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    hash::Hash
+};
+
+/// Floyd's tortoise and hare, returns `(prefix_len, period)` of the sequence `start, step(start), ..`
+///
+/// Only keeps two states around, but evaluates `step` roughly three times per element.
+pub fn floyd<S: Clone + Eq>(start: &S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut slow = step(start);
+    let mut fast = step(&slow);
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        fast = step(&fast);
+    }
+    let mut prefix = 0;
+    slow = start.clone();
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        prefix += 1;
+    }
+    let mut period = 1;
+    fast = step(&slow);
+    while slow != fast {
+        fast = step(&fast);
+        period += 1;
+    }
+    (prefix, period)
+}
+
+/// Brent's power-of-two variant, returns `(prefix_len, period)` of the sequence `start, step(start), ..`
+pub fn brent<S: Clone + Eq>(start: &S, mut step: impl FnMut(&S) -> S) -> (usize, usize) {
+    let mut power = 1;
+    let mut period = 1;
+    let mut slow = start.clone();
+    let mut fast = step(start);
+    while slow != fast {
+        if power == period {
+            slow = fast.clone();
+            power *= 2;
+            period = 0;
+        }
+        fast = step(&fast);
+        period += 1;
+    }
+    let mut slow = start.clone();
+    let mut fast = (0..period).fold(start.clone(), |s, _| step(&s) );
+    let mut prefix = 0;
+    while slow != fast {
+        slow = step(&slow);
+        fast = step(&fast);
+        prefix += 1;
+    }
+    (prefix, period)
+}
+
+/// Hash based detection, returns `(prefix_len, period)` of the sequence `start, step(start), ..`
+///
+/// Only the `fingerprint` of each state is stored, which has to be unique for all visited states.
+/// Use `Clone::clone` to store full states.
+#[inline]
+pub fn hashed<S, K: Eq + Hash>(
+    start: &S,
+    step: impl FnMut(&S) -> S,
+    fingerprint: impl FnMut(&S) -> K
+) -> (usize, usize) {
+    let (prefix, period, _) = detect(start, step, fingerprint, |_| ());
+    (prefix, period)
+}
+
+#[inline]
+fn detect<S, K: Eq + Hash, V>(
+    start: &S,
+    mut step: impl FnMut(&S) -> S,
+    mut fingerprint: impl FnMut(&S) -> K,
+    mut value: impl FnMut(&S) -> V
+) -> (usize, usize, Vec<V>) {
+    let mut visited = HashMap::new();
+    let mut values = Vec::new();
+    visited.insert(fingerprint(start), 0);
+    values.push(value(start));
+    let mut state = step(start);
+    for i in 1.. {
+        match visited.entry(fingerprint(&state)) {
+            Entry::Occupied(e) => return (*e.get(), i - e.get(), values),
+            Entry::Vacant(e) => _ = e.insert(i)
+        }
+        values.push(value(&state));
+        state = step(&state);
+    }
+    unreachable!()
+}
+
+/// maps step `n` to the equivalent step within the first `prefix_len + period` steps
+#[inline(always)]
+pub fn reduce(n: usize, (prefix, period): (usize, usize)) -> usize {
+    if n < prefix {
+        n
+    } else {
+        prefix + (n - prefix) % period
+    }
+}
+
+/// state after `n` steps, simulating at most `prefix_len + period` of them
+#[inline]
+pub fn nth<S: Clone>(start: &S, mut step: impl FnMut(&S) -> S, n: usize, cycle: (usize, usize)) -> S {
+    (0..reduce(n, cycle)).fold(start.clone(), |s, _| step(&s) )
+}
+
+/// `value` of the state after `n` steps
+///
+/// Detects the cycle by `fingerprint` (see `hashed`) and only keeps the derived values around.
+#[inline]
+pub fn nth_value<S, K: Eq + Hash, V>(
+    start: &S,
+    step: impl FnMut(&S) -> S,
+    fingerprint: impl FnMut(&S) -> K,
+    value: impl FnMut(&S) -> V,
+    n: usize
+) -> V {
+    let (prefix, period, mut values) = detect(start, step, fingerprint, value);
+    values.swap_remove(reduce(n, (prefix, period)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 0 -> 3 -> 6 -> 9 -> 1 -> 4 -> 7 -> 10 -> 2 -> 5 -> 8 -> 0 -> ..
+    #[inline]
+    fn step(x: &u32) -> u32 {
+        (x + 3) % 11
+    }
+
+    // 0 -> 1 -> .. -> 5 -> 6 -> .. -> 11 -> 5 -> ..
+    #[inline]
+    fn rho(x: &u32) -> u32 {
+        if *x < 5 { x + 1 } else { 5 + (x - 5 + 1) % 7 }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        for start in 0..12 {
+            let expected = if start < 5 { (5 - start as usize, 7) } else { (0, 7) };
+            assert_eq!(floyd(&start, rho), expected);
+            assert_eq!(brent(&start, rho), expected);
+            assert_eq!(hashed(&start, rho, Clone::clone), expected);
+        }
+        assert_eq!(floyd(&0, step), (0, 11));
+        assert_eq!(brent(&0, step), (0, 11));
+        assert_eq!(hashed(&0, step, Clone::clone), (0, 11));
+    }
+
+    #[test]
+    fn extrapolate() {
+        let cycle = brent(&0, rho);
+        for n in [0, 3, 5, 12, 1_000_000_000] {
+            let naive = (0..n.min(5) + (n.max(5) - 5) % 7).fold(0, |s, _| rho(&s) );
+            assert_eq!(nth(&0, rho, n, cycle), naive);
+            assert_eq!(nth_value(&0, rho, |x| x % 16, |x| x * 2, n), naive * 2);
+        }
+    }
+}
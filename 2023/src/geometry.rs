@@ -0,0 +1,162 @@
+use itertools::Itertools;
+use ndarray::Array2;
+use num::Integer;
+
+pub type Point = [i64; 2];
+
+/// twice the signed area of the closed polygon through `points`, positive for counter-clockwise loops
+#[inline]
+pub fn shoelace(points: &[Point]) -> i64 {
+    points.iter().circular_tuple_windows()
+        .map( |(a, b)| a[0] * b[1] - a[1] * b[0] )
+        .sum()
+}
+
+/// number of lattice points on the closed polygon through `points`
+#[inline]
+pub fn boundary(points: &[Point]) -> u64 {
+    points.iter().circular_tuple_windows()
+        .map( |(a, b)| (b[0] - a[0]).unsigned_abs().gcd(&(b[1] - a[1]).unsigned_abs()) )
+        .sum()
+}
+
+/// euclidean length of the closed polygon through `points`
+#[inline]
+pub fn perimeter(points: &[Point]) -> f64 {
+    points.iter().circular_tuple_windows()
+        .map( |(a, b)| ((b[0] - a[0]) as f64).hypot((b[1] - a[1]) as f64) )
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lattice {
+    pub double_area: u64,
+    pub boundary: u64,
+    pub interior: u64
+}
+impl Lattice {
+    #[inline(always)]
+    pub fn area(&self) -> f64 {
+        self.double_area as f64 / 2.0
+    }
+    /// lattice points inside or on the polygon
+    #[inline(always)]
+    pub fn total(&self) -> u64 {
+        self.interior + self.boundary
+    }
+}
+
+/// area and lattice point counts of a simple polygon using Pick's theorem
+///
+/// `points` are the corners (or any points along the edges) in walking order.
+#[inline]
+pub fn pick(points: &[Point]) -> Lattice {
+    let double_area = shoelace(points).unsigned_abs();
+    let boundary = boundary(points);
+    // A = i + b/2 - 1
+    let interior = if double_area == 0 { 0 } else { (double_area + 2 - boundary) / 2 };
+    Lattice { double_area, boundary, interior }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Boundary,
+    Inside,
+    Outside
+}
+
+/// labels every non-wall cell that is connected (von Neumann) to the edge of the grid as outside
+pub fn flood_fill(walls: &Array2<bool>) -> Array2<Region> {
+    let (w, h) = walls.dim();
+    let mut result = walls.map( |wall| if *wall { Region::Boundary } else { Region::Inside } );
+    if w == 0 || h == 0 { return result; }
+    let mut stack = (0..w).flat_map( |x| [[x, 0], [x, h - 1]] )
+        .chain((0..h).flat_map( |y| [[0, y], [w - 1, y]] ))
+        .collect_vec();
+    while let Some([x, y]) = stack.pop() {
+        if result[[x, y]] != Region::Inside { continue; }
+        result[[x, y]] = Region::Outside;
+        if x > 0 { stack.push([x - 1, y]); }
+        if x + 1 < w { stack.push([x + 1, y]); }
+        if y > 0 { stack.push([x, y - 1]); }
+        if y + 1 < h { stack.push([x, y + 1]); }
+    }
+    result
+}
+
+/// labels the cells of a `(width, height)` grid relative to the closed loop through `path`
+///
+/// Consecutive points have to be in the same row or column. The grid is refined internally,
+/// so outside cells are found even if they can only be reached by squeezing between loop tiles.
+pub fn enclose((w, h): (usize, usize), path: &[[usize; 2]]) -> Array2<Region> {
+    // cell [x, y] maps to [2x + 1, 2y + 1], the extra rim keeps the outside connected
+    let mut walls = Array2::from_elem((2 * w + 1, 2 * h + 1), false);
+    for (a, b) in path.iter().circular_tuple_windows() {
+        // ASSERT: a and b are in the same row or column
+        let (x0, x1) = (2 * a[0].min(b[0]) + 1, 2 * a[0].max(b[0]) + 1);
+        let (y0, y1) = (2 * a[1].min(b[1]) + 1, 2 * a[1].max(b[1]) + 1);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                walls[[x, y]] = true;
+            }
+        }
+    }
+    let refined = flood_fill(&walls);
+    Array2::from_shape_fn((w, h), |(x, y)| refined[[2 * x + 1, 2 * y + 1]] )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SQUARE: &[Point] = &[[0, 0], [3, 0], [3, 3], [0, 3]];
+    const NOTCH: &[[usize; 2]] = &[[0, 0], [6, 0], [6, 4], [4, 4], [4, 2], [2, 2], [2, 4], [0, 4]];
+    // the pocket around [3, 3] is only open through the gap between [2, 5] and [3, 5]
+    const SQUEEZE: &[[usize; 2]] = &[
+        [0, 0], [6, 0], [6, 5], [3, 5], [3, 4], [4, 4],
+        [4, 2], [2, 2], [2, 5], [0, 5]
+    ];
+
+    #[inline]
+    fn points(path: &[[usize; 2]]) -> Vec<Point> {
+        path.iter().map( |[x, y]| [*x as i64, *y as i64] ).collect()
+    }
+
+    #[inline]
+    fn count(regions: &Array2<Region>, region: Region) -> u64 {
+        regions.iter().filter( |r| **r == region ).count() as u64
+    }
+
+    #[test]
+    fn polygon() {
+        assert_eq!(shoelace(SQUARE), 18);
+        assert_eq!(shoelace(&SQUARE.iter().rev().copied().collect_vec()), -18);
+        assert_eq!(pick(SQUARE), Lattice { double_area: 18, boundary: 12, interior: 4 });
+        assert_eq!(perimeter(SQUARE), 12.0);
+        let triangle = [[0, 0], [4, 0], [0, 3]];
+        assert_eq!(perimeter(&triangle), 12.0);
+        assert_eq!(pick(&triangle), Lattice { double_area: 12, boundary: 8, interior: 3 });
+        assert_eq!(pick(&points(NOTCH)), Lattice { double_area: 40, boundary: 24, interior: 9 });
+    }
+
+    #[test]
+    fn regions() {
+        for (dim, path) in [((7, 5), NOTCH), ((7, 6), SQUEEZE)] {
+            let regions = enclose(dim, path);
+            let lattice = pick(&points(path));
+            assert_eq!(count(&regions, Region::Boundary), lattice.boundary);
+            assert_eq!(count(&regions, Region::Inside), lattice.interior);
+            assert_eq!(regions[[3, 3]], Region::Outside);
+        }
+        let walls = enclose((7, 6), SQUEEZE).map( |r| *r == Region::Boundary );
+        // without refining the pocket looks enclosed
+        assert_eq!(flood_fill(&walls)[[3, 3]], Region::Inside);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(flood_fill(&Array2::from_elem((0, 0), false)).dim(), (0, 0));
+        assert_eq!(flood_fill(&Array2::from_elem((3, 0), false)).dim(), (3, 0));
+        assert_eq!(flood_fill(&Array2::from_elem((0, 2), true)).dim(), (0, 2));
+    }
+}
@@ -0,0 +1,336 @@
+use std::slice;
+use bit_vec::BitVec;
+use ndarray::Array2;
+use petgraph::{
+    Directed, Direction,
+    visit::{
+        GraphBase, GraphProp, Data, EdgeRef, VisitMap, Visitable,
+        IntoNeighbors, IntoNeighborsDirected, IntoEdges, IntoEdgeReferences, IntoNodeIdentifiers,
+        NodeCount, NodeIndexable, NodeCompactIndexable
+    }
+};
+
+pub type Pos = [usize; 2];
+
+pub const VON_NEUMANN: &[[isize; 2]] = &[[1, 0], [0, -1], [-1, 0], [0, 1]];
+pub const MOORE: &[[isize; 2]] = &[
+    [1, 0], [1, -1], [0, -1], [-1, -1],
+    [-1, 0], [-1, 1], [0, 1], [1, 1]
+];
+
+/// Zero-copy view of a 2D grid (indexed by `[x, y]`) as a directed petgraph graph.
+///
+/// Every cell is a node, `rule` lists the offsets of potential neighbours and
+/// `edge(from, &from_tile, to, &to_tile)` decides passability and the edge weight.
+#[derive(Debug, Clone, Copy)]
+pub struct GridGraph<'a, T, F> {
+    grid: &'a Array2<T>,
+    rule: &'a [[isize; 2]],
+    edge: F
+}
+impl<'a, T, F> GridGraph<'a, T, F> {
+    #[inline]
+    pub fn new<E>(grid: &'a Array2<T>, rule: &'a [[isize; 2]], edge: F) -> Self
+    where
+        F: Fn(Pos, &T, Pos, &T) -> Option<E>
+    {
+        Self { grid, rule, edge }
+    }
+    #[inline(always)]
+    pub fn grid(&self) -> &'a Array2<T> {
+        self.grid
+    }
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.grid.dim().0
+    }
+    #[inline]
+    fn shift(&self, [x, y]: Pos, [dx, dy]: [isize; 2]) -> Option<Pos> {
+        let (w, h) = self.grid.dim();
+        let x = x.checked_add_signed(dx).filter( |x| *x < w )?;
+        let y = y.checked_add_signed(dy).filter( |y| *y < h )?;
+        Some([x, y])
+    }
+    #[inline]
+    fn walk(&self, node: Pos, direction: Direction) -> Edges<'_, 'a, T, F> {
+        Edges { graph: self, node, direction, offsets: self.rule.iter() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridEdge<E> {
+    source: Pos,
+    target: Pos,
+    weight: E
+}
+impl<E: Copy> EdgeRef for GridEdge<E> {
+    type NodeId = Pos;
+    type EdgeId = (Pos, Pos);
+    type Weight = E;
+    #[inline(always)]
+    fn source(&self) -> Pos {
+        self.source
+    }
+    #[inline(always)]
+    fn target(&self) -> Pos {
+        self.target
+    }
+    #[inline(always)]
+    fn weight(&self) -> &E {
+        &self.weight
+    }
+    #[inline(always)]
+    fn id(&self) -> (Pos, Pos) {
+        (self.source, self.target)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Edges<'b, 'a, T, F> {
+    graph: &'b GridGraph<'a, T, F>,
+    node: Pos,
+    direction: Direction,
+    offsets: slice::Iter<'a, [isize; 2]>
+}
+impl<'b, 'a, T, F, E> Iterator for Edges<'b, 'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type Item = GridEdge<E>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for &[dx, dy] in self.offsets.by_ref() {
+            let (source, target) = match self.direction {
+                Direction::Outgoing => match self.graph.shift(self.node, [dx, dy]) {
+                    Some(other) => (self.node, other),
+                    None => continue
+                },
+                Direction::Incoming => match self.graph.shift(self.node, [-dx, -dy]) {
+                    Some(other) => (other, self.node),
+                    None => continue
+                }
+            };
+            let grid = self.graph.grid;
+            if let Some(weight) = (self.graph.edge)(source, &grid[source], target, &grid[target]) {
+                return Some(GridEdge { source, target, weight });
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Neighbors<'b, 'a, T, F>(Edges<'b, 'a, T, F>);
+impl<'b, 'a, T, F, E> Iterator for Neighbors<'b, 'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type Item = Pos;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = self.0.next()?;
+        Some(match self.0.direction {
+            Direction::Outgoing => edge.target,
+            Direction::Incoming => edge.source
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeIdentifiers {
+    width: usize,
+    range: std::ops::Range<usize>
+}
+impl Iterator for NodeIdentifiers {
+    type Item = Pos;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map( |i| [i % self.width, i / self.width] )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EdgeReferences<'b, 'a, T, F> {
+    nodes: NodeIdentifiers,
+    edges: Option<Edges<'b, 'a, T, F>>
+}
+impl<'b, 'a, T, F, E> Iterator for EdgeReferences<'b, 'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type Item = GridEdge<E>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let edges = self.edges.as_mut()?;
+            if let Some(edge) = edges.next() {
+                return Some(edge);
+            }
+            let graph = edges.graph;
+            self.edges = self.nodes.next().map( |node| graph.walk(node, Direction::Outgoing) );
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GridVisitMap {
+    visited: BitVec,
+    width: usize
+}
+impl VisitMap<Pos> for GridVisitMap {
+    #[inline]
+    fn visit(&mut self, [x, y]: Pos) -> bool {
+        let i = y * self.width + x;
+        let new = !self.visited[i];
+        self.visited.set(i, true);
+        new
+    }
+    #[inline]
+    fn is_visited(&self, [x, y]: &Pos) -> bool {
+        self.visited[y * self.width + x]
+    }
+}
+
+impl<T, F> GraphBase for GridGraph<'_, T, F> {
+    type NodeId = Pos;
+    type EdgeId = (Pos, Pos);
+}
+impl<T, F> GraphProp for GridGraph<'_, T, F> {
+    type EdgeType = Directed;
+}
+impl<T, F, E> Data for GridGraph<'_, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type NodeWeight = T;
+    type EdgeWeight = E;
+}
+impl<T, F> NodeCount for GridGraph<'_, T, F> {
+    #[inline(always)]
+    fn node_count(&self) -> usize {
+        self.grid.len()
+    }
+}
+impl<T, F> NodeIndexable for GridGraph<'_, T, F> {
+    #[inline(always)]
+    fn node_bound(&self) -> usize {
+        self.grid.len()
+    }
+    #[inline(always)]
+    fn to_index(&self, [x, y]: Pos) -> usize {
+        y * self.width() + x
+    }
+    #[inline(always)]
+    fn from_index(&self, i: usize) -> Pos {
+        [i % self.width(), i / self.width()]
+    }
+}
+impl<T, F> NodeCompactIndexable for GridGraph<'_, T, F> {}
+impl<T, F> Visitable for GridGraph<'_, T, F> {
+    type Map = GridVisitMap;
+    #[inline]
+    fn visit_map(&self) -> Self::Map {
+        GridVisitMap { visited: BitVec::from_elem(self.grid.len(), false), width: self.width() }
+    }
+    #[inline]
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.visited = BitVec::from_elem(self.grid.len(), false);
+        map.width = self.width();
+    }
+}
+
+impl<'b, 'a, T, F, E> IntoNeighbors for &'b GridGraph<'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type Neighbors = Neighbors<'b, 'a, T, F>;
+    #[inline]
+    fn neighbors(self, a: Pos) -> Self::Neighbors {
+        Neighbors(self.walk(a, Direction::Outgoing))
+    }
+}
+impl<'b, 'a, T, F, E> IntoNeighborsDirected for &'b GridGraph<'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type NeighborsDirected = Neighbors<'b, 'a, T, F>;
+    #[inline]
+    fn neighbors_directed(self, a: Pos, direction: Direction) -> Self::NeighborsDirected {
+        Neighbors(self.walk(a, direction))
+    }
+}
+impl<'b, 'a, T, F, E: Copy> IntoEdgeReferences for &'b GridGraph<'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type EdgeRef = GridEdge<E>;
+    type EdgeReferences = EdgeReferences<'b, 'a, T, F>;
+    #[inline]
+    fn edge_references(self) -> Self::EdgeReferences {
+        let mut nodes = self.node_identifiers();
+        let edges = nodes.next().map( |node| self.walk(node, Direction::Outgoing) );
+        EdgeReferences { nodes, edges }
+    }
+}
+impl<'b, 'a, T, F, E: Copy> IntoEdges for &'b GridGraph<'a, T, F>
+where
+    F: Fn(Pos, &T, Pos, &T) -> Option<E>
+{
+    type Edges = Edges<'b, 'a, T, F>;
+    #[inline]
+    fn edges(self, a: Pos) -> Self::Edges {
+        self.walk(a, Direction::Outgoing)
+    }
+}
+impl<T, F> IntoNodeIdentifiers for &GridGraph<'_, T, F> {
+    type NodeIdentifiers = NodeIdentifiers;
+    #[inline]
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        NodeIdentifiers { width: self.width(), range: 0..self.grid.len() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use petgraph::algo::{dijkstra, connected_components, tarjan_scc, toposort};
+    use crate::parse::*;
+
+    #[test]
+    fn weighted_dijkstra() {
+        let grid = parse("131\n919\n111\n", grid(&mut |c| c as u8 - b'0'))
+            .unwrap();
+        let graph = GridGraph::new(&grid, VON_NEUMANN, |_, _, _, to| Some(*to as u32));
+        let costs = dijkstra(&graph, [0, 0], None, |e| *e.weight());
+        assert_eq!(costs.len(), 9);
+        assert_eq!(costs[&[2, 0]], 4);
+        assert_eq!(costs[&[0, 2]], 6);
+    }
+
+    #[test]
+    fn passable_components() {
+        let grid = parse("..#..\n..#..\n##.##\n.#...\n", grid(&mut |c| c == '.'))
+            .unwrap();
+        let graph = GridGraph::new(&grid, VON_NEUMANN, |_, a, _, b| (*a && *b).then_some(()));
+        // every wall tile is a component on its own
+        let walls = grid.iter().filter( |x| !**x ).count();
+        assert_eq!(connected_components(&graph), 4 + walls);
+        let largest = tarjan_scc(&graph).into_iter().map( |c| c.len() ).max().unwrap();
+        assert_eq!(largest, 4);
+        let graph = GridGraph::new(&grid, MOORE, |_, a, _, b| (*a && *b).then_some(()));
+        assert_eq!(connected_components(&graph), 2 + walls);
+    }
+
+    #[test]
+    fn downhill_toposort() {
+        let grid = parse("987\n856\n123\n", grid(&mut |c| c as u8 - b'0'))
+            .unwrap();
+        let graph = GridGraph::new(&grid, VON_NEUMANN, |_, a, _, b| (a > b).then_some(()));
+        let order = toposort(&graph, None).unwrap();
+        assert_eq!(order.len(), 9);
+        let rank = |pos| order.iter().position( |p| *p == pos ).unwrap();
+        assert!(graph.edge_references().all( |e| rank(e.source()) < rank(e.target()) ));
+        let graph = GridGraph::new(&grid, VON_NEUMANN, |_, a, _, b| (a != b).then_some(()));
+        assert!(toposort(&graph, None).is_err());
+    }
+}
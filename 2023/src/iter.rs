@@ -98,7 +98,6 @@ where
     F: FnMut(&N) -> Option<I>,
     G: FnMut(&N) -> P
 {
-    #[allow(dead_code)]
     #[inline]
     pub fn new(neighbours: F, mut path_map: G, root: N) -> Self {
         let path = vec![path_map(&root)];
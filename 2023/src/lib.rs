@@ -15,6 +15,17 @@
 #![feature(linked_list_cursors)]
 
 pub mod puzzles;
+pub mod bits;
+pub mod compress;
+pub mod crt;
+pub mod cycle;
+pub mod geometry;
+pub mod graph;
+pub mod piecewise;
+pub mod quadratic;
+pub mod search;
+pub mod sequence;
+pub mod state;
 mod parse;
 mod iter;
 
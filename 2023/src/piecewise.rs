@@ -0,0 +1,266 @@
+use std::{
+    ops::Range,
+    iter::once
+};
+use itertools::Itertools;
+use num_traits::{PrimInt, Signed};
+use tap::Pipe;
+
+/// Piecewise shift `x -> x + shift[i]`, where `i` is the number of borders `<= x`.
+///
+/// The domain is `T::min_value()..T::max_value()`, all pieces have slope one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseLinear<T> {
+    borders: Vec<T>,
+    shift: Vec<T>,
+}
+impl<T> PiecewiseLinear<T> {
+    /// constant shift by `default` everywhere
+    #[inline]
+    pub fn new(default: T) -> Self {
+        Self { borders: Vec::new(), shift: vec![default] }
+    }
+}
+impl<T: PrimInt> PiecewiseLinear<T> {
+    /// maps `src..src + len` to `dst..dst + len` for each `(dst, src, len)` and is the identity elsewhere
+    ///
+    /// ASSERT: source ranges do not overlap
+    pub fn from_segments(segments: impl IntoIterator<Item = (T, T, T)>) -> Self {
+        let mut segments = segments.into_iter().collect_vec();
+        segments.sort_unstable_by_key( |(_, src, _)| *src );
+        let (mut borders, mut shift) = (segments.len() << 1)
+            .pipe( |n| (Vec::with_capacity(n), Vec::with_capacity(n)) );
+        shift.push(T::zero());
+        for (dst, src, len) in segments.into_iter() {
+            if borders.last().is_some_and( |last| *last == src ) {
+                *shift.last_mut().unwrap() = dst - src;
+            } else {
+                borders.push(src);
+                shift.push(dst - src);
+            }
+            borders.push(src + len);
+            shift.push(T::zero());
+        }
+        Self { shift, borders }
+    }
+    #[inline]
+    pub fn apply(&self, value: T) -> T {
+        self.borders.partition_point( |border| *border <= value)
+            .pipe( |i| value + self.shift[i] )
+    }
+    /// splits `range` into the pieces it overlaps, yielding `(sub_range, shift)`
+    #[inline]
+    pub fn range(&self, range: Range<T>) -> Iter<T> {
+        Iter { pl: self, range, position: None }
+    }
+    /// composition, applying `self` first and `other` second
+    pub fn contract(self, other: Self) -> Self {
+        let (mut borders, shift) = self.pieces()
+            // the unbounded first and last piece are clamped to the domain
+            .flat_map( |(start, end, shift)|
+                other.range(start.saturating_add(shift)..end.saturating_add(shift))
+                    .map( move |(range, value)| (range.end - shift, shift + value) )
+            )
+            .unzip::<T, T, Vec<_>, Vec<_>>();
+        borders.pop();
+        Self { borders, shift }
+    }
+    /// `(start, end, shift)` for all pieces covering the domain
+    #[inline]
+    fn pieces(&self) -> impl Iterator<Item = (T, T, T)> + '_ {
+        once(T::min_value())
+            .chain(self.borders.iter().copied())
+            .chain(once(T::max_value()))
+            .tuple_windows()
+            .zip(self.shift.iter().copied())
+            .map( |((start, end), shift)| (start, end, shift) )
+    }
+    /// images of all `intervals`, sorted and merged
+    pub fn map_intervals(&self, intervals: impl IntoIterator<Item = Range<T>>) -> Vec<Range<T>> {
+        let mut images = intervals.into_iter()
+            .filter( |range| !range.is_empty() )
+            .flat_map( |range| self.range(range) )
+            .map( |(range, shift)| (range.start + shift)..(range.end + shift) )
+            .collect_vec();
+        images.sort_unstable_by_key( |range| range.start );
+        images.into_iter()
+            .coalesce( |a, b| if b.start <= a.end { Ok(a.start..a.end.max(b.end)) } else { Err((a, b)) } )
+            .collect()
+    }
+    /// smallest output for any input in `range`
+    #[inline]
+    pub fn min_over(&self, range: Range<T>) -> Option<T> {
+        if range.is_empty() { return None; }
+        // every piece is increasing, so only the starts need to be checked
+        self.range(range)
+            .map( |(range, shift)| range.start + shift )
+            .min()
+    }
+}
+impl<T: PrimInt + Signed> PiecewiseLinear<T> {
+    /// inverse map, if every output is hit exactly once
+    pub fn inverse(&self) -> Option<Self> {
+        let mut images = self.pieces()
+            .filter( |(start, end, _)| start < end )
+            .map( |(start, end, shift)| Some((start.checked_add(&shift)?, end.checked_add(&shift)?, shift)) )
+            .collect::<Option<Vec<_>>>()?;
+        images.sort_unstable_by_key( |(start, _, _)| *start );
+        let mut borders = Vec::with_capacity(images.len());
+        let mut shift = Vec::with_capacity(images.len());
+        let mut expected = T::min_value();
+        for (start, end, s) in images {
+            if start != expected { return None; }
+            if start != T::min_value() {
+                if shift.last().is_some_and( |last| *last == -s ) {
+                    expected = end;
+                    continue;
+                }
+                borders.push(start);
+            }
+            shift.push(-s);
+            expected = end;
+        }
+        (expected == T::max_value()).then_some(Self { borders, shift })
+    }
+}
+
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    pl: &'a PiecewiseLinear<T>,
+    range: Range<T>,
+    position: Option<(bool, usize)>
+}
+impl<'a, T: PrimInt> Iterator for Iter<'a, T> {
+    type Item = (Range<T>, T);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((last, i)) = self.position {
+            if last { return None; }
+            if let Some(end) = self.pl.borders.get(i + 1)
+                .and_then( |&end| if end < self.range.end { Some(end) } else { None } )
+            {
+                self.position = Some((false, i + 1));
+                Some((self.pl.borders[i]..end, self.pl.shift[i + 1]))
+            } else {
+                self.position = Some((true, i + 1));
+                Some((self.pl.borders[i]..self.range.end, self.pl.shift[i + 1]))
+            }
+        } else {
+            let i = self.pl.borders
+                .partition_point( |border| *border <= self.range.start );
+            if i == self.pl.borders.len() || self.pl.borders[i] >= self.range.end {
+                self.position = Some((true, i));
+                Some((self.range.clone(), self.pl.shift[i]))
+            } else {
+                self.position = Some((false, i));
+                Some((self.range.start..self.pl.borders[i], self.pl.shift[i]))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    const DOMAIN: Range<i64> = -10..160;
+
+    /// non-overlapping `(dst, src, len)` segments inside `0..150`
+    fn segments() -> impl Strategy<Value = Vec<(i64, i64, i64)>> {
+        prop::collection::vec((0..10i64, 1..20i64, 0..100i64), 0..6)
+            .prop_map( |parts| {
+                let mut src = 0;
+                parts.into_iter()
+                    .map( |(gap, len, dst)| {
+                        src += gap;
+                        let segment = (dst, src, len);
+                        src += len;
+                        segment
+                    } )
+                    .filter( |(_, src, len)| src + len <= 150 )
+                    .collect()
+            } )
+    }
+
+    /// maps a shuffled partition of `0..total` back onto `0..total`
+    fn permutation() -> impl Strategy<Value = Vec<(i64, i64, i64)>> {
+        prop::collection::vec(1..20i64, 1..6)
+            .prop_flat_map( |lens| {
+                let order = Just((0..lens.len()).collect_vec()).prop_shuffle();
+                (Just(lens), order)
+            } )
+            .prop_map( |(lens, order)| {
+                let starts = once(0)
+                    .chain(lens.iter().scan(0, |end, len| { *end += len; Some(*end) }))
+                    .collect_vec();
+                let mut dst = 0;
+                order.into_iter()
+                    .map( |i| {
+                        let segment = (dst, starts[i], lens[i]);
+                        dst += lens[i];
+                        segment
+                    } )
+                    .collect()
+            } )
+    }
+
+    proptest! {
+        #[test]
+        fn contract_matches_apply(a in segments(), b in segments()) {
+            let (a, b) = (PiecewiseLinear::from_segments(a), PiecewiseLinear::from_segments(b));
+            let ab = a.clone().contract(b.clone());
+            for x in DOMAIN {
+                prop_assert_eq!(ab.apply(x), b.apply(a.apply(x)));
+            }
+        }
+
+        #[test]
+        fn contract_with_default(a in segments(), d in -50..50i64, e in -50..50i64) {
+            let a = PiecewiseLinear::from_segments(a);
+            let (d, e) = (PiecewiseLinear::new(d), PiecewiseLinear::new(e));
+            let da = d.clone().contract(a.clone());
+            let ad = a.clone().contract(d.clone());
+            let de = d.clone().contract(e.clone());
+            for x in DOMAIN {
+                prop_assert_eq!(da.apply(x), a.apply(d.apply(x)));
+                prop_assert_eq!(ad.apply(x), d.apply(a.apply(x)));
+                prop_assert_eq!(de.apply(x), e.apply(d.apply(x)));
+            }
+        }
+
+        #[test]
+        fn intervals_match_apply(a in segments(), start in -10..150i64, len in 0..40i64) {
+            let a = PiecewiseLinear::from_segments(a);
+            let images = a.map_intervals(once(start..(start + len)));
+            let mut expected = (start..(start + len)).map( |x| a.apply(x) ).collect_vec();
+            expected.sort_unstable();
+            expected.dedup();
+            prop_assert_eq!(images.iter().cloned().flatten().collect_vec(), expected.clone());
+            prop_assert_eq!(a.min_over(start..(start + len)), expected.first().copied());
+            prop_assert!(images.windows(2).all( |w| w[0].end < w[1].start ));
+        }
+
+        #[test]
+        fn inverse_roundtrip(a in permutation()) {
+            let a = PiecewiseLinear::from_segments(a);
+            let inverse = a.inverse().unwrap();
+            for x in DOMAIN {
+                prop_assert_eq!(inverse.apply(a.apply(x)), x);
+            }
+            let twice = inverse.inverse().unwrap();
+            for x in DOMAIN {
+                prop_assert_eq!(twice.apply(x), a.apply(x));
+            }
+        }
+    }
+
+    #[test]
+    fn not_bijective() {
+        let a = PiecewiseLinear::from_segments([(0, 10, 5), (2, 20, 5)]);
+        assert_eq!(a.inverse(), None);
+        let b = PiecewiseLinear::from_segments([(100, 0, 5)]);
+        assert_eq!(b.inverse(), None);
+        assert_eq!(PiecewiseLinear::new(0).inverse(), Some(PiecewiseLinear::new(0)));
+    }
+}
@@ -2,13 +2,12 @@ use std::{
     borrow::Cow,
     mem::transmute
 };
-use bit_vec::BitVec;
 use itertools::Itertools;
 use ndarray::prelude::*;
 use nom::IResult;
 use tap::Pipe as TapPipe;
 
-use crate::{*, parse::*};
+use crate::{*, parse::*, geometry::*};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -196,26 +195,12 @@ pub fn part1(input: &str) -> Answer {
 pub fn part2(input: &str) -> Answer {
     parse(input, grid)?
         .pipe( |(mut grid, start)| {
-            let (height, width) = grid.dim();
             let (dir, _) = patch(&mut grid, start).dirs().unwrap();
-            let mut path = BitVec::from_elem(width * height, false);
-            for ((row, column), _) in Walker::new(&grid, start, dir)
+            let path = Walker::new(&grid, start, dir)
                 .take_while_inclusive( |(pos, _)| *pos != start )
-            {
-                path.set(row * width + column, true);
-            }
-            let mut sum = 0;
-            let mut is_inside = false;
-            for (i, pipe) in grid.iter().enumerate() {
-                if path[i] {
-                    if matches!(pipe, Pipe::NS | Pipe::NW | Pipe::NE) {
-                        is_inside = !is_inside;
-                    }
-                } else if is_inside {
-                    sum += 1;
-                }  
-            }
-            sum
+                .map( |((row, column), _)| [column as i64, row as i64] )
+                .collect_vec();
+            pick(&path).interior
         } )
         .pipe( |result| Ok(Cow::Owned(result.to_string())) )
 }
@@ -1,13 +1,9 @@
-use std::{
-    borrow::Cow,
-    collections::hash_map::Entry
-};
-use bit_vec::BitVec;
+use std::borrow::Cow;
 use ndarray::prelude::*;
 use nom::IResult;
-use tap::Pipe;
+use tap::{Tap, Pipe};
 
-use crate::{*, parse::*};
+use crate::{*, parse::*, bits::*, cycle::*};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
@@ -124,38 +120,18 @@ impl Platform {
             .sum::<usize>()
     }
     #[inline]
-    fn is_round(&self) -> BitVec {
-        let (w, h) = self.0.dim();
-        let mut round = BitVec::from_elem(w * h, false);
-        for y in 0..h {
-            for x in 0..w {
-                if self.0[[x, y]] == Tile::Round {
-                    round.set(y * w + x, true);
-                }
-            }
-        }
-        round
+    fn is_round(&self) -> Box<[u64]> {
+        BitGrid::from_fn(self.0.dim(), |pos| self.0[pos] == Tile::Round ).fingerprint()
     }
     #[inline]
-    fn stabilized_load(&mut self, count: usize) -> usize {
-        let mut visited = HashMap::new();
-        visited.insert(self.is_round(), 0);
-        for i in 1..=count {
-            self.cycle();
-            let round = self.is_round();
-            match visited.entry(round) {
-                Entry::Occupied(e) => {
-                    let period = i - e.get();
-                    let remaining = (count - i) % period;
-                    for _ in 0..remaining {
-                        self.cycle();
-                    }
-                    return self.load();
-                },
-                Entry::Vacant(e) => _ = e.insert(i)
-            }
-        }
-        self.load()
+    fn stabilized_load(&self, count: usize) -> usize {
+        nth_value(
+            self,
+            |platform| platform.clone().tap_mut(Self::cycle),
+            Self::is_round,
+            Self::load,
+            count
+        )
     }
 }
 
@@ -170,9 +146,7 @@ pub fn part1(input: &str) -> Answer {
 
 pub fn part2(input: &str) -> Answer {
     parse(input, Platform::parse)?
-        .pipe( |mut platform| {
-            platform.stabilized_load(1_000_000_000)
-        } )
+        .pipe( |platform| platform.stabilized_load(1_000_000_000) )
         .pipe( |result| Ok(Cow::Owned(result.to_string())) )
 }
 
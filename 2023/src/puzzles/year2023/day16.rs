@@ -1,8 +1,7 @@
 use std::{
     borrow::Cow,
-    collections::{VecDeque, HashSet}
+    collections::VecDeque
 };
-use bit_vec::BitVec;
 use ndarray::Array2;
 use petgraph::{
     graph::DiGraph,
@@ -10,7 +9,7 @@ use petgraph::{
 };
 use tap::Pipe;
 
-use crate::{*, parse::*};
+use crate::{*, parse::*, bits::*, state::*};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Dir {
@@ -19,6 +18,17 @@ enum Dir {
     W,
     S
 }
+impl StateIndex for Dir {
+    type Bound = ();
+    #[inline(always)]
+    fn count(_: ()) -> usize {
+        4
+    }
+    #[inline(always)]
+    fn index(&self, _: ()) -> usize {
+        *self as usize
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Splitter {
@@ -120,17 +130,14 @@ fn parse_graph(grid: &Array2<Tile>, pos: Pos, dir: Dir) -> (Graph, <Graph as Gra
     let mut graph = Graph::default();
     let root = graph.add_node((pos, grid[pos]));
     let mut open = VecDeque::new();
-    let mut closed = HashSet::new();
+    let (w, h) = grid.dim();
+    let mut closed = DenseSet::<(Pos, Dir)>::new(([w, h], ()));
     open.push_back((root, RayCast(pos, dir, 0)));
-    let max = {
-        let dim = grid.dim();
-        [dim.0 - 1, dim.1 - 1]
-    };
+    let max = [w - 1, h - 1];
     while let Some((parent, mut raycast)) = open.pop_back() {
         while grid[raycast.0] == Tile::Empty && raycast.step(max) {}
         let (pos, dir, tile) = (raycast.0, raycast.1, grid[raycast.0]);
-        if closed.contains(&(pos, dir)) { continue; }
-        closed.insert((pos, dir));
+        if !closed.insert((pos, dir)) { continue; }
         let node = graph.add_node((pos, tile));
         graph.add_edge(parent, node, (dir, raycast.2));
         match tile {
@@ -159,12 +166,11 @@ fn parse_graph(grid: &Array2<Tile>, pos: Pos, dir: Dir) -> (Graph, <Graph as Gra
 }
 
 fn count_energy(graph: &Graph, root: <Graph as GraphBase>::NodeId, [w, h]: Pos) -> usize {
-    let mut grid = BitVec::from_elem(w * h, false);
+    let mut grid = BitGrid::new((w, h));
     depth_first_search(&graph, [root], |event| {
         match event {
             DfsEvent::Discover(node, _) => {
-                let ([x, y], _) = graph[node];
-                grid.set(y * w + x, true);
+                grid.insert(graph[node].0);
             },
             DfsEvent::TreeEdge(a, b) => {
                 let (pos, _) = graph[a];
@@ -172,15 +178,14 @@ fn count_energy(graph: &Graph, root: <Graph as GraphBase>::NodeId, [w, h]: Pos)
                 let (dir, len) = graph[edge];
                 let mut raycast = RayCast::try_from(pos, dir, [w, h]).unwrap();
                 for _ in 0..len {
-                    let [x, y] = raycast.0;
-                    grid.set(y * w + x, true);
+                    grid.insert(raycast.0);
                     raycast.step([w, h]);
                 }
             },
             _ => ()
         }
     } );
-    grid.into_iter().filter( |x| *x ).count()
+    grid.count()
 }
 
 pub fn part1(input: &str) -> Answer {
@@ -1,53 +1,9 @@
 use std::{
     borrow::Cow,
-    ops::Add,
-    hash::Hash,
-    collections::HashSet,
-    cmp::Reverse,
     mem::transmute
 };
-use keyed_priority_queue::{KeyedPriorityQueue, Entry};
-use num_traits::Zero;
-
-use crate::{*, parse::*};
-
-fn astar<T, X, S, N, NR, H, G>(start: S, mut neighbours: N, mut heuristic: H, mut goal: G)
-    -> Option<X>
-where
-    T: Copy + Eq + Hash,
-    X: Copy + Ord + Add<X, Output = X> + Zero,
-    S: IntoIterator<Item = T>,
-    N: FnMut(T) -> NR,
-    NR: IntoIterator<Item = (T, X)>,
-    H: FnMut(T) -> X,
-    G: FnMut(T) -> bool
-{
-    let mut front = KeyedPriorityQueue::new();
-    let mut closed = HashSet::new();
-    for t in start {
-        front.push(t, Reverse((heuristic(t), X::zero())));
-    }
-    while let Some((t, Reverse((_, g)))) = front.pop() {
-        if goal(t) {
-            return Some(g);
-        }
-        closed.insert(t);
-        for (n, c) in neighbours(t).into_iter()
-            .filter( |(n, _)| !closed.contains(n) )
-        {
-            let g = g + c;
-            let p = Reverse((g + heuristic(n), g));
-            match front.entry(n) {
-                Entry::Vacant(e) =>
-                    e.set_priority(p),
-                Entry::Occupied(e) if *e.get_priority() < p =>
-                    _ = e.set_priority(p),
-                _ => ()
-            }
-        }
-    }
-    None
-}
+
+use crate::{*, parse::*, search::*, state::*};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -59,6 +15,23 @@ enum Dir {
     W = 0xf0,
     S = 0xff
 }
+impl StateIndex for Dir {
+    type Bound = ();
+    #[inline(always)]
+    fn count(_: ()) -> usize {
+        5
+    }
+    #[inline]
+    fn index(&self, _: ()) -> usize {
+        match self {
+            Dir::Unknown => 0,
+            Dir::E => 1,
+            Dir::N => 2,
+            Dir::W => 3,
+            Dir::S => 4
+        }
+    }
+}
 impl Dir {
     #[inline(always)]
     fn invert(self) -> Self {
@@ -78,6 +51,17 @@ enum ValidResult {
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 struct Constraint<const MIN: u8, const MAX: u8>(Dir, u8);
+impl<const MIN: u8, const MAX: u8> StateIndex for Constraint<MIN, MAX> {
+    type Bound = ();
+    #[inline(always)]
+    fn count(_: ()) -> usize {
+        Dir::count(()) * (MAX as usize + 1)
+    }
+    #[inline(always)]
+    fn index(&self, _: ()) -> usize {
+        self.0.index(()) * (MAX as usize + 1) + self.1 as usize
+    }
+}
 impl<const MIN: u8, const MAX: u8> Constraint<MIN, MAX> {
     #[inline]
     fn valid(&self, dir: Dir) -> ValidResult {
@@ -108,58 +92,77 @@ impl<const MIN: u8, const MAX: u8> Constraint<MIN, MAX> {
     }
 }
 
-pub fn part1(input: &str) -> Answer {
-    type Node = ([usize; 2], Constraint<0, 3>);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// A* with dense state storage
+    AStar,
+    /// A* with hashed state storage
+    AStarHashed,
+    Dial
+}
+
+fn solve<const MIN: u8, const MAX: u8>(input: &str, method: Method) -> Answer {
     let grid = parse(input, grid(&mut |c| c as u8 - b'0' ))?;
     let (w, h) = grid.dim();
     let node = #[inline]
-        |(i, c): Node, dir: Dir|
-            c.concat(dir).and_then( |h| 
-                grid.get(i).map( |x| ((i, h), *x as u16) )
+        |(i, c): ([usize; 2], Constraint<MIN, MAX>), dir: Dir|
+            c.concat(dir).and_then( |c|
+                grid.get(i).map( |x| ((i, c), *x) )
             );
     let neighbours = #[inline]
-        |([x, y], c): Node|
+        |([x, y], c): ([usize; 2], Constraint<MIN, MAX>)|
             node(([x.wrapping_sub(1), y], c), Dir::W).into_iter()
                 .chain(node(([x, y.wrapping_sub(1)], c), Dir::N))
                 .chain(node(([x + 1, y], c), Dir::E))
                 .chain(node(([x, y + 1], c), Dir::S));
-    let heuristic = #[inline]
-        |([x, y], _): Node|
-            ((w - x - 1) + (h - y - 1)) as u16;
     let goal = #[inline]
-        |(i, _): Node|
-            i == [w - 1, h - 1];
+        |(i, c): ([usize; 2], Constraint<MIN, MAX>)|
+            i == [w - 1, h - 1] && c.can_stop();
     let start = [([0, 0], Constraint::default())];
-    let result = astar(start, neighbours, heuristic, goal)
-        .expect("valid path");
+    let bound = ([w, h], ());
+    let result = match method {
+        Method::AStar | Method::AStarHashed => {
+            let heuristic = #[inline]
+                |([x, y], _): ([usize; 2], Constraint<MIN, MAX>)|
+                    ((w - x - 1) + (h - y - 1)) as u16;
+            let neighbours = #[inline]
+                |n| neighbours(n).map( |(n, c)| (n, c as u16) );
+            let cost = if method == Method::AStar {
+                astar_in(Dense(bound), start, neighbours, heuristic, goal, Record::NONE)
+                    .map( |path| path.cost )
+            } else {
+                astar_in(Hashed, start, neighbours, heuristic, goal, Record::NONE)
+                    .map( |path| path.cost )
+            };
+            cost.expect("valid path") as usize
+        },
+        Method::Dial => {
+            let neighbours = #[inline]
+                |n| neighbours(n).map( |(n, c)| (n, c as usize) );
+            dial(start, neighbours, goal, 9, bound)
+                .expect("valid path").1
+        }
+    };
     Ok(Cow::Owned(result.to_string()))
 }
+
+#[inline]
+pub fn part1_with(input: &str, method: Method) -> Answer {
+    solve::<0, 3>(input, method)
+}
+
 // 1310 too low, 1327 too high
+#[inline]
+pub fn part2_with(input: &str, method: Method) -> Answer {
+    solve::<4, 10>(input, method)
+}
+
+pub fn part1(input: &str) -> Answer {
+    part1_with(input, Method::AStar)
+}
+
 pub fn part2(input: &str) -> Answer {
-    type Node = ([usize; 2], Constraint<4, 10>);
-    let grid = parse(input, grid(&mut |c| c as u8 - b'0' ))?;
-    let (w, h) = grid.dim();
-    let node = #[inline]
-        |(i, h): Node, dir: Dir|
-            h.concat(dir).and_then( |c| 
-                grid.get(i).map( |x| ((i, c), *x as u16) )
-            );
-    let neighbours = #[inline]
-        |([x, y], c): Node|
-            node(([x.wrapping_sub(1), y], c), Dir::W).into_iter()
-                .chain(node(([x, y.wrapping_sub(1)], c), Dir::N))
-                .chain(node(([x + 1, y], c), Dir::E))
-                .chain(node(([x, y + 1], c), Dir::S));
-    let heuristic = #[inline]
-        |([x, y], _): Node|
-            ((w - x - 1) + (h - y - 1)) as u16;
-    let goal = #[inline]
-        |(i, c): Node|
-            i == [w - 1, h - 1] && c.can_stop();
-    let start = [([0, 0], Constraint::default())];
-    let result = astar(start, neighbours, heuristic, goal)
-        .expect("valid path");
-    Ok(Cow::Owned(result.to_string()))
+    part2_with(input, Method::AStar)
 }
 
 inventory::submit! { Puzzle::new(2023, 17, 1, part1) }
@@ -205,4 +208,17 @@ mod test {
     fn test2() {
         assert_eq!(OUTPUT2, &part2(INPUT2).unwrap());
     }
+
+    #[test]
+    fn dial() {
+        assert_eq!(OUTPUT1, &part1_with(INPUT1, Method::Dial).unwrap());
+        assert_eq!(OUTPUT2, &part2_with(INPUT2, Method::Dial).unwrap());
+        assert_eq!("94", &part2_with(INPUT1, Method::Dial).unwrap());
+    }
+
+    #[test]
+    fn hashed() {
+        assert_eq!(OUTPUT1, &part1_with(INPUT1, Method::AStarHashed).unwrap());
+        assert_eq!(OUTPUT2, &part2_with(INPUT2, Method::AStarHashed).unwrap());
+    }
 }
\ No newline at end of file
@@ -1,8 +1,4 @@
-use std::{
-    borrow::Cow,
-    ops::Range,
-    iter::once
-};
+use std::borrow::Cow;
 use itertools::Itertools;
 use nom::{
     IResult,
@@ -15,109 +11,24 @@ use nom::{
 use num_traits::PrimInt;
 use tap::Pipe;
 
-use crate::{*, parse::*};
+use crate::{*, parse::*, piecewise::*};
 
-#[derive(Debug, Clone)]
-struct PiecewiseLinear<T> {
-    borders: Vec<T>,
-    shift: Vec<T>,
-}
-impl<T> PiecewiseLinear<T> {
-    #[inline]
-    fn new(default: T) -> Self {
-        Self { borders: Vec::new(), shift: vec![default] }
-    }
-}
-impl<T: PrimInt> PiecewiseLinear<T> {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        let (input, mut segments) = preceded(
-            many_till(anychar, line_ending), 
-            separated_list0(line_ending,
-                tuple((
+fn map<T: PrimInt>(input: &str) -> IResult<&str, PiecewiseLinear<T>> {
+    let (input, segments) = preceded(
+        many_till(anychar, line_ending),
+        separated_list0(line_ending,
+            tuple((
+                map_res(digit1, |number| T::from_str_radix(number, 10) ),
+                delimited(
+                    char(' '),
                     map_res(digit1, |number| T::from_str_radix(number, 10) ),
-                    delimited(
-                        char(' '),
-                        map_res(digit1, |number| T::from_str_radix(number, 10) ),
-                        char(' ')
-                    ),
-                    map_res(digit1, |number| T::from_str_radix(number, 10))
-                ))
-            )
-        )(input)?;
-        segments.sort_unstable_by_key( |(_, src, _)| *src );
-        let (mut borders, mut shift) = (segments.len() << 1)
-            .pipe( |n| (Vec::with_capacity(n), Vec::with_capacity(n)) );
-        shift.push(T::zero());
-        for (dst, src, len) in segments.into_iter() {
-            if borders.last().is_some_and( |last| *last == src ) { 
-                *shift.last_mut().unwrap() = dst - src;
-            } else {
-                borders.push(src);
-                shift.push(dst - src);
-            }
-            borders.push(src + len);
-            shift.push(T::zero());
-        }
-        Ok((input, Self { shift, borders }))
-    }
-    #[inline]
-    fn apply(&self, value: T) -> T {
-        self.borders.partition_point( |border| *border <= value)
-            .pipe( |i| value + self.shift[i] )
-    }
-    #[inline]
-    fn range(&self, range: Range<T>) -> Iter<T> {
-        Iter { pl: self, range, position: None }
-    }
-    fn contract(self, other: Self) -> Self {
-        let (mut borders, shift) = once(T::min_value())
-            .chain(self.borders)
-            .chain(once(T::max_value()))
-            .tuple_windows()
-            .zip(self.shift)
-            .flat_map( |((start, end), shift)|
-                other.range((start + shift)..(end + shift))
-                    .map( move |(range, value)| (range.end - shift, shift + value) )
-            )
-            .unzip::<T, T, Vec<_>, Vec<_>>();
-        borders.pop();
-        Self { borders, shift }
-    }
-}
-
-#[derive(Debug)]
-struct Iter<'a, T> {
-    pl: &'a PiecewiseLinear<T>,
-    range: Range<T>,
-    position: Option<(bool, usize)>
-}
-impl<'a, T: PrimInt> Iterator for Iter<'a, T> {
-    type Item = (Range<T>, T);
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some((last, i)) = self.position {
-            if last { return None; }
-            if let Some(end) = self.pl.borders.get(i + 1)
-                .and_then( |&end| if end < self.range.end { Some(end) } else { None } ) 
-            {
-                self.position = Some((false, i + 1));
-                Some((self.pl.borders[i]..end, self.pl.shift[i + 1]))
-            } else {
-                self.position = Some((true, i + 1));
-                Some((self.pl.borders[i]..self.range.end, self.pl.shift[i + 1]))
-            }
-        } else {
-            let i = self.pl.borders
-                .partition_point( |border| *border <= self.range.start );
-            if i == self.pl.borders.len() || self.pl.borders[i] >= self.range.end {
-                self.position = Some((true, i));
-                Some((self.range.clone(), self.pl.shift[i]))
-            } else {
-                self.position = Some((false, i));
-                Some((self.range.start..self.pl.borders[i], self.pl.shift[i]))
-            }
-        }
-    }
+                    char(' ')
+                ),
+                map_res(digit1, |number| T::from_str_radix(number, 10))
+            ))
+        )
+    )(input)?;
+    Ok((input, PiecewiseLinear::from_segments(segments)))
 }
 
 #[inline]
@@ -129,7 +40,7 @@ fn seeds<T: PrimInt>(input: &str) -> IResult<&str, Vec<T>> {
 
 #[inline]
 fn maps<T: PrimInt>(input: &str) -> IResult<&str, Vec<PiecewiseLinear<T>>> {
-    separated_list0(count(line_ending, 2), PiecewiseLinear::parse)(input)
+    separated_list0(count(line_ending, 2), map)(input)
 }
 
 pub fn part1(input: &str) -> Answer {
@@ -154,8 +65,7 @@ pub fn part2(input: &str) -> Answer {
                 .pipe( |map|
                     seeds.into_iter()
                         .tuples()
-                        .flat_map( |(start, len)| map.range(start..(start + len)) )
-                        .map( |(range, value)| range.start + value )
+                        .filter_map( |(start, len)| map.min_over(start..(start + len)) )
                         .min().unwrap()
                 )
         )
@@ -169,6 +79,7 @@ inventory::submit! { Puzzle::new(2023, 5, 2, part2) }
 mod test {
     use super::*;
     use indoc::indoc;
+    use std::iter::once;
     use itertools::unfold;
 
     const INPUT1: &str = indoc! {"
@@ -1,8 +1,6 @@
 use std::{
     borrow::Cow,
 };
-use glam::{DVec4, U64Vec4};
-use itertools::Itertools;
 use nom::{
     IResult,
     bytes::complete::tag,
@@ -12,35 +10,30 @@ use nom::{
 };
 use tap::Pipe;
 
-use crate::{*, parse::*};
+use crate::{*, parse::*, quadratic::*};
 
 struct Param {
-    time: DVec4,
-    distance: DVec4
+    time: Vec<i128>,
+    distance: Vec<i128>
 }
 impl Param {
     fn parse1(input: &str) -> IResult<&str, Self> {
-        let (input, (mut time, mut distance)) = separated_pair(
+        let (input, (time, distance)) = separated_pair(
             preceded(
                 pair(tag("Time:"), many0(char(' '))),
                 separated_list1(many1(char(' ')), 
-                    map_res(digit1, |number: &str| number.parse::<f64>())
+                    map_res(digit1, |number: &str| number.parse::<i128>())
                 )
             ),
             line_ending,
             preceded(
                 pair(tag("Distance:"), many0(char(' '))),
                 separated_list1(many1(char(' ')), 
-                    map_res(digit1, |number: &str| number.parse::<f64>())
+                    map_res(digit1, |number: &str| number.parse::<i128>())
                 )
             ),
         )(input)?;
-        time.resize(4, 0.0);
-        distance.resize(4, 0.0);
-        Ok((input, Self { 
-            time: DVec4::from_slice(&time),
-            distance: DVec4::from_slice(&distance)
-        }))
+        Ok((input, Self { time, distance }))
     }
     fn parse2(input: &str) -> IResult<&str, Self> {
         let (input, (time, distance)) = separated_pair(
@@ -55,33 +48,29 @@ impl Param {
                 )
             )(input)?;
         Ok((input, Self { 
-            time: DVec4::splat(time.join("").parse::<f64>().unwrap()),
-            distance: DVec4::splat(distance.join("").parse::<f64>().unwrap())
+            time: vec![time.join("").parse::<i128>().unwrap()],
+            distance: vec![distance.join("").parse::<i128>().unwrap()]
         }))
     }
+    /// number of ways to beat each record: `t * (time - t) > distance`
     #[inline]
-    fn count(&self) -> U64Vec4 {
-        const EPSILON: f64 = 1e-3;
-        let (a, d) = (0.5 * self.time, self.distance);
-        let a2 = a * a;
-        let b = (a2 - d).powf(0.5);
-        ((a + b - EPSILON).ceil() - (a - b + EPSILON).ceil()).as_u64vec4()
+    fn count(&self) -> impl Iterator<Item = u128> + '_ {
+        self.time.iter().zip(&self.distance)
+            .map( |(time, distance)|
+                solve(-1, *time, -distance, Relation::Greater).count().unwrap()
+            )
     }
 }
 
-#[inline]
-fn prod(v: U64Vec4) -> u64 {
-    v.to_array().iter().take_while( |x| **x != 0).product1().unwrap()
-}
-
 pub fn part1(input: &str) -> Answer {
     parse(input, Param::parse1)?.count()
-        .pipe(prod)
+        .product::<u128>()
         .pipe( |result| Ok(Cow::Owned(result.to_string())) )
 }
 
 pub fn part2(input: &str) -> Answer {
-    parse(input, Param::parse2)?.count().x
+    parse(input, Param::parse2)?.count()
+        .product::<u128>()
         .pipe( |result| Ok(Cow::Owned(result.to_string())) )
 }
 
@@ -1,6 +1,7 @@
 use std::{
     borrow::Cow,
-    collections::HashSet
+    collections::HashSet,
+    iter
 };
 use itertools::Itertools;
 use nom::{
@@ -10,10 +11,9 @@ use nom::{
     sequence::{separated_pair, delimited, preceded},
     combinator::iterator, multi::many1
 };
-use num::Integer;
 use tap::{Tap, Pipe};
 
-use crate::{*, parse::*};
+use crate::{*, parse::*, cycle::*, crt::*};
 
 #[derive(Debug)]
 struct BinaryGraph<'a> {
@@ -86,27 +86,24 @@ impl<'a> BinaryGraph<'a> {
         }
         self.pos.iter().all( |p| self.goal.contains(p) )
     }
-    fn cycles(&self, instructions: &[char]) -> Vec<(usize, usize)> {
-        // ASSERT: no goal is met before the cycle starts
-        // ASSERT: the cycle only contains a single goal once
+    fn orbits(&self, instructions: &[char]) -> Vec<Orbit> {
         let len = instructions.len();
+        let step = |&(pos, i): &(usize, usize)| {
+            let pos = match instructions[i] {
+                'L' => self.edges[pos].0,
+                'R' => self.edges[pos].1,
+                _ => panic!()
+            };
+            (pos, (i + 1) % len)
+        };
         self.pos.iter().map( |start| {
-            let (mut pos, mut i, mut g) = (*start, 0, None);
-            loop {
-                pos = match instructions[i % len] {
-                    'L' => self.edges[pos].0,
-                    'R' => self.edges[pos].1,
-                    _ => panic!()
-                };
-                i += 1;
-                if self.goal.contains(&pos) {
-                    if let Some(g) = g {
-                        return (g, i - g);
-                    } else {
-                        g = Some(i);
-                    }
-                }
-            }
+            let start = (*start, 0);
+            let (prefix, period) = brent(&start, step);
+            let goals = iter::successors(Some(start), |s| Some(step(s)) )
+                .take(prefix + period)
+                .positions( |(pos, _)| self.goal.contains(&pos) )
+                .collect_vec();
+            Orbit::new(prefix, period, goals)
         } ).collect_vec()
     }
 }
@@ -116,17 +113,6 @@ fn instructions(input: &str) -> IResult<&str, Vec<char>> {
     many1(one_of("LR"))(input)
 }
 
-fn sync(a: (usize, usize), b: (usize, usize)) -> (usize, usize) {
-    let (a, m, b, n) = (a.0 as i128, a.1 as i128, b.0 as i128, b.1 as i128);
-    let egcd = m.extended_gcd(&n);
-    let (q, r) = (b - a).div_mod_floor(&egcd.gcd);
-    if r != 0 { panic!(); }
-    let p = m / egcd.gcd * n;
-    let min = a.min(b);
-    let o = (q * m * egcd.x + min - a).mod_floor(&p) + min;
-    (o as usize, p as usize)
-}
-
 pub fn part1(input: &str) -> Answer {
     parse(input, separated_pair(instructions, line_ending, BinaryGraph::parse_sequential))?
         .pipe( |(inst, mut graph)| 
@@ -135,12 +121,10 @@ pub fn part1(input: &str) -> Answer {
         )
         .pipe( |result| Ok(Cow::Owned(result.to_string())) )
 }
-// FIXME: 11623902991577 is too low
 pub fn part2(input: &str) -> Answer {
     parse(input, separated_pair(instructions, line_ending, BinaryGraph::parse_parallel))?
         .pipe( |(inst, graph)| 
-            graph.cycles(&inst).into_iter()
-                .fold((1, 1), sync).0
+            synchronise(&graph.orbits(&inst)).expect("walkers meet")
         )
         .pipe( |result| Ok(Cow::Owned(result.to_string())) )
 }
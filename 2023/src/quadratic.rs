@@ -0,0 +1,163 @@
+use num::Integer;
+use num_traits::{PrimInt, Unsigned};
+
+/// `floor(sqrt(n))`, computed digit by digit
+pub fn isqrt<T: PrimInt + Unsigned>(n: T) -> T {
+    if n.is_zero() { return n; }
+    let bits = T::zero().count_zeros();
+    let mut bit = T::one() << ((bits - 1 - n.leading_zeros()) & !1) as usize;
+    let (mut rest, mut result) = (n, T::zero());
+    while !bit.is_zero() {
+        if rest >= result + bit {
+            rest = rest - (result + bit);
+            result = (result >> 1) + bit;
+        } else {
+            result = result >> 1;
+        }
+        bit = bit >> 2;
+    }
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual
+}
+
+/// Integer solutions of a quadratic inequality, `i128::MIN` and `i128::MAX` stand for unbounded ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solution {
+    Empty,
+    /// every integer in `start..=end`
+    Within(i128, i128),
+    /// every integer except the ones in `start..=end`
+    Outside(i128, i128)
+}
+impl Solution {
+    #[inline]
+    pub fn contains(&self, x: i128) -> bool {
+        match *self {
+            Self::Empty => false,
+            Self::Within(start, end) => start <= x && x <= end,
+            Self::Outside(start, end) => x < start || end < x
+        }
+    }
+    /// number of solutions, `None` if there are infinitely many
+    #[inline]
+    pub fn count(&self) -> Option<u128> {
+        match *self {
+            Self::Empty => Some(0),
+            Self::Within(i128::MIN, _) | Self::Within(_, i128::MAX) | Self::Outside(..) => None,
+            Self::Within(start, end) => Some(start.abs_diff(end) + 1)
+        }
+    }
+}
+
+/// all integers `x` with `a*x^2 + b*x + c <relation> 0`
+///
+/// Exact as long as `b^2 - 4ac` and the evaluation near the roots fit into `i128`.
+pub fn solve(a: i128, b: i128, c: i128, relation: Relation) -> Solution {
+    // over the integers `f >= 0` is the same as `f + 1 > 0`
+    match relation {
+        Relation::Greater => positive(a, b, c),
+        Relation::GreaterEqual => positive(a, b, c + 1),
+        Relation::Less => positive(-a, -b, -c),
+        Relation::LessEqual => positive(-a, -b, 1 - c)
+    }
+}
+
+/// solutions of `a*x^2 + b*x + c > 0`
+fn positive(a: i128, b: i128, c: i128) -> Solution {
+    match a.signum() {
+        0 => match b.signum() {
+            0 => if c > 0 { Solution::Within(i128::MIN, i128::MAX) } else { Solution::Empty },
+            1 => Solution::Within(Integer::div_floor(&-c, &b) + 1, i128::MAX),
+            _ => Solution::Within(i128::MIN, -Integer::div_floor(&-c, &-b) - 1)
+        },
+        1 => match non_positive(a, b, c) {
+            Some((start, end)) => Solution::Outside(start, end),
+            None => Solution::Within(i128::MIN, i128::MAX)
+        },
+        // `f > 0` is the same as `-f + 1 <= 0`
+        _ => match non_positive(-a, -b, -c + 1) {
+            Some((start, end)) => Solution::Within(start, end),
+            None => Solution::Empty
+        }
+    }
+}
+
+/// integer interval with `a*x^2 + b*x + c <= 0` for `a > 0`
+fn non_positive(a: i128, b: i128, c: i128) -> Option<(i128, i128)> {
+    let f = |x: i128| (a * x + b) * x + c;
+    let discriminant = b * b - 4 * a * c;
+    if discriminant < 0 { return None; }
+    let root = isqrt(discriminant as u128) as i128;
+    // the real roots lie within (-b -+ (root + 1)) / 2a, so each guess is off by at most one
+    let mut start = -Integer::div_floor(&(b + root), &(2 * a));
+    if f(start - 1) <= 0 { start -= 1; }
+    let mut end = Integer::div_floor(&(root - b), &(2 * a));
+    if f(end + 1) <= 0 { end += 1; }
+    (start <= end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn square_roots() {
+        for n in 0..10_000u64 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n);
+        }
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        let n = (1u128 << 100) - 1;
+        assert_eq!(isqrt(n), (1 << 50) - 1);
+        assert_eq!(isqrt(n + 1), 1 << 50);
+    }
+
+    #[test]
+    fn brute_force() {
+        use Relation::*;
+        for a in -3..=3 {
+            for b in -6..=6 {
+                for c in -6..=6 {
+                    for relation in [Less, LessEqual, Greater, GreaterEqual] {
+                        let solution = solve(a, b, c, relation);
+                        for x in -50..=50 {
+                            let y = a * x * x + b * x + c;
+                            let expected = match relation {
+                                Less => y < 0,
+                                LessEqual => y <= 0,
+                                Greater => y > 0,
+                                GreaterEqual => y >= 0
+                            };
+                            assert_eq!(solution.contains(x), expected, "{a}x^2 + {b}x + {c} {relation:?} 0 at {x}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn touching_roots() {
+        // t * (T - t) > D with equality at both ends
+        assert_eq!(solve(-1, 30, -200, Relation::Greater), Solution::Within(11, 19));
+        assert_eq!(solve(-1, 30, -200, Relation::GreaterEqual), Solution::Within(10, 20));
+        // D is far beyond the exact integer range of f64
+        let (time, t0) = (1_000_000_001i128, 123_456_789i128);
+        let distance = t0 * (time - t0);
+        assert_eq!(
+            solve(-1, time, -distance, Relation::Greater),
+            Solution::Within(t0 + 1, time - t0 - 1)
+        );
+        let (time, t0) = (3_000_000_000_000_001i128, 1_234_567_890_123i128);
+        let distance = t0 * (time - t0) - 1;
+        assert_eq!(solve(-1, time, -distance, Relation::Greater).count(), Some((time - 2 * t0 + 1) as u128));
+    }
+}
@@ -0,0 +1,513 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    cmp::{Reverse, Ordering},
+    hash::Hash,
+    ops::Add
+};
+use keyed_priority_queue::{KeyedPriorityQueue, Entry};
+use num_traits::Zero;
+use smallvec::SmallVec;
+
+use crate::{iter::PathIter, state::*};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Record {
+    pub path: bool,
+    pub expanded: bool
+}
+impl Record {
+    pub const NONE: Self = Self { path: false, expanded: false };
+    pub const PATH: Self = Self { path: true, expanded: false };
+    pub const EXPANDED: Self = Self { path: false, expanded: true };
+    pub const ALL: Self = Self { path: true, expanded: true };
+}
+
+#[derive(Debug, Clone)]
+pub struct Found<T, X, C = HashSet<T>> {
+    pub goal: T,
+    pub cost: X,
+    /// from a start node to `goal` (both inclusive), only present with `Record::path`
+    pub path: Option<Vec<T>>,
+    /// all nodes whose neighbours were generated, only present with `Record::expanded`
+    pub expanded: Option<C>
+}
+
+#[inline]
+fn backtrack<T: Copy, M: StateMap<T, T>>(parents: &M, goal: T) -> Vec<T> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(&parent) = parents.get(&current) {
+        path.push(parent);
+        current = parent;
+    }
+    path.reverse();
+    path
+}
+
+pub fn astar_in<T, X, R, S, N, NR, H, G>(storage: R, start: S, mut neighbours: N, mut heuristic: H, mut goal: G, record: Record)
+    -> Option<Found<T, X, R::Set>>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    R: Storage<T>,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>,
+    H: FnMut(T) -> X,
+    G: FnMut(T) -> bool
+{
+    // ASSERT: heuristic is consistent (never overestimates a single step)
+    let mut front = KeyedPriorityQueue::new();
+    let mut closed = storage.set();
+    let mut parents = storage.map();
+    for t in start {
+        front.push(t, Reverse((heuristic(t), X::zero())));
+    }
+    while let Some((t, Reverse((_, g)))) = front.pop() {
+        if goal(t) {
+            return Some(Found {
+                goal: t,
+                cost: g,
+                path: record.path.then( || backtrack(&parents, t) ),
+                expanded: record.expanded.then_some(closed)
+            });
+        }
+        closed.insert(t);
+        for (n, c) in neighbours(t).into_iter()
+            .filter( |(n, _)| !closed.contains(n) )
+        {
+            let g = g + c;
+            let p = Reverse((g + heuristic(n), g));
+            match front.entry(n) {
+                Entry::Vacant(e) =>
+                    e.set_priority(p),
+                Entry::Occupied(e) if *e.get_priority() < p =>
+                    _ = e.set_priority(p),
+                _ => continue
+            }
+            if record.path {
+                parents.insert(n, t);
+            }
+        }
+    }
+    None
+}
+
+#[inline]
+pub fn astar<T, X, S, N, NR, H, G>(start: S, neighbours: N, heuristic: H, goal: G, record: Record)
+    -> Option<Found<T, X>>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>,
+    H: FnMut(T) -> X,
+    G: FnMut(T) -> bool
+{
+    astar_in(Hashed, start, neighbours, heuristic, goal, record)
+}
+
+#[inline]
+pub fn dijkstra_in<T, X, R, S, N, NR, G>(storage: R, start: S, neighbours: N, goal: G, record: Record)
+    -> Option<Found<T, X, R::Set>>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    R: Storage<T>,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>,
+    G: FnMut(T) -> bool
+{
+    astar_in(storage, start, neighbours, |_| X::zero(), goal, record)
+}
+
+#[inline]
+pub fn dijkstra<T, X, S, N, NR, G>(start: S, neighbours: N, goal: G, record: Record)
+    -> Option<Found<T, X>>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>,
+    G: FnMut(T) -> bool
+{
+    dijkstra_in(Hashed, start, neighbours, goal, record)
+}
+
+pub fn bfs_in<T, R, S, N, NR, G>(storage: R, start: S, mut neighbours: N, mut goal: G, record: Record)
+    -> Option<Found<T, usize, R::Set>>
+where
+    T: Copy,
+    R: Storage<T>,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = T>,
+    G: FnMut(T) -> bool
+{
+    let mut front = VecDeque::new();
+    let mut seen = storage.set();
+    let mut expanded = storage.set();
+    let mut parents = storage.map();
+    for t in start {
+        if seen.insert(t) {
+            front.push_back((t, 0));
+        }
+    }
+    while let Some((t, g)) = front.pop_front() {
+        if goal(t) {
+            return Some(Found {
+                goal: t,
+                cost: g,
+                path: record.path.then( || backtrack(&parents, t) ),
+                expanded: record.expanded.then_some(expanded)
+            });
+        }
+        if record.expanded {
+            expanded.insert(t);
+        }
+        for n in neighbours(t) {
+            if !seen.insert(n) { continue; }
+            if record.path {
+                parents.insert(n, t);
+            }
+            front.push_back((n, g + 1));
+        }
+    }
+    None
+}
+
+#[inline]
+pub fn bfs<T, S, N, NR, G>(start: S, neighbours: N, goal: G, record: Record)
+    -> Option<Found<T, usize>>
+where
+    T: Copy + Eq + Hash,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = T>,
+    G: FnMut(T) -> bool
+{
+    bfs_in(Hashed, start, neighbours, goal, record)
+}
+
+/// Dijkstra on a circular bucket queue for small integer weights (at most `max_weight`)
+///
+/// panics if an edge weight exceeds `max_weight`
+pub fn dial<T, S, N, NR, G>(start: S, mut neighbours: N, mut goal: G, max_weight: usize, bound: T::Bound)
+    -> Option<(T, usize)>
+where
+    T: Copy + StateIndex,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, usize)>,
+    G: FnMut(T) -> bool
+{
+    let len = max_weight + 1;
+    let mut buckets = (0..len).map( |_| Vec::new() ).collect::<Vec<_>>();
+    let mut distance = vec![usize::MAX; T::count(bound)];
+    let mut closed = DenseSet::<T>::new(bound);
+    let mut pending = 0;
+    for t in start {
+        distance[t.index(bound)] = 0;
+        buckets[0].push(t);
+        pending += 1;
+    }
+    let mut g = 0;
+    while pending != 0 {
+        let bucket = g % len;
+        while let Some(t) = buckets[bucket].pop() {
+            pending -= 1;
+            if distance[t.index(bound)] != g || !closed.insert(t) { continue; }
+            if goal(t) {
+                return Some((t, g));
+            }
+            for (n, c) in neighbours(t) {
+                assert!(c <= max_weight, "edge weight {c} exceeds max_weight {max_weight}");
+                let (i, g) = (n.index(bound), g + c);
+                if distance[i] <= g || closed.contains(&n) { continue; }
+                distance[i] = g;
+                buckets[g % len].push(n);
+                pending += 1;
+            }
+        }
+        g += 1;
+    }
+    None
+}
+
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<T, X> {
+    distance: HashMap<T, X>,
+    predecessors: HashMap<T, SmallVec<[T; 2]>>,
+    goals: Vec<T>
+}
+impl<T: Copy + Eq + Hash, X: Copy + Ord> ShortestPaths<T, X> {
+    #[inline]
+    pub fn distance(&self, t: &T) -> Option<X> {
+        self.distance.get(t).copied()
+    }
+    #[inline]
+    pub fn distances(&self) -> &HashMap<T, X> {
+        &self.distance
+    }
+    /// all neighbours that reach `t` on some shortest path
+    #[inline]
+    pub fn predecessors(&self, t: &T) -> &[T] {
+        self.predecessors.get(t).map_or(&[], |p| p.as_slice())
+    }
+    /// goals that were reached at the optimal cost (only filled by `shortest_paths_to`)
+    #[inline]
+    pub fn goals(&self) -> &[T] {
+        &self.goals
+    }
+    /// every node that lies on at least one shortest path to any of `goals`
+    pub fn on_any_path<I: IntoIterator<Item = T>>(&self, goals: I) -> HashSet<T> {
+        let mut open = goals.into_iter()
+            .filter( |goal| self.distance.contains_key(goal) )
+            .collect::<Vec<_>>();
+        let mut result = open.iter().copied().collect::<HashSet<_>>();
+        while let Some(t) = open.pop() {
+            for p in self.predecessors(&t) {
+                if result.insert(*p) {
+                    open.push(*p);
+                }
+            }
+        }
+        result
+    }
+    pub fn count_paths(&self, goal: T) -> usize {
+        let mut nodes = self.on_any_path([goal]).into_iter().collect::<Vec<_>>();
+        nodes.sort_unstable_by_key( |t| self.distance[t] );
+        let mut count = HashMap::with_capacity(nodes.len());
+        for t in nodes {
+            let predecessors = self.predecessors(&t);
+            let n = if predecessors.is_empty() {
+                1
+            } else {
+                predecessors.iter().map( |p| count[p] ).sum()
+            };
+            count.insert(t, n);
+        }
+        count.get(&goal).copied().unwrap_or(0)
+    }
+    /// enumerates every shortest path from a start node to `goal` (both inclusive)
+    pub fn paths(&self, goal: T) -> impl '_ + Iterator<Item = Vec<T>> {
+        let neighbours = |t: &T| {
+            let predecessors = self.predecessors(t);
+            (!predecessors.is_empty()).then( || predecessors.iter().copied() )
+        };
+        let (single, tree) = match self.predecessors(&goal) {
+            _ if !self.distance.contains_key(&goal) => (None, None),
+            [] => (Some(vec![goal]), None),
+            _ => (None, Some(PathIter::new(neighbours, |t| *t, goal)))
+        };
+        single.into_iter()
+            .chain(tree.into_iter().flatten())
+            .map( |mut path| { path.reverse(); path } )
+    }
+}
+
+fn all_paths<T, X, S, N, NR, G>(start: S, mut neighbours: N, mut goal: Option<G>) -> ShortestPaths<T, X>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>,
+    G: FnMut(T) -> bool
+{
+    // ASSERT: all edge weights are positive
+    let mut front = KeyedPriorityQueue::new();
+    let mut result = ShortestPaths {
+        distance: HashMap::new(),
+        predecessors: HashMap::new(),
+        goals: Vec::new()
+    };
+    let mut bound = None;
+    for t in start {
+        front.push(t, Reverse(X::zero()));
+    }
+    while let Some((t, Reverse(g))) = front.pop() {
+        if bound.is_some_and( |bound| g > bound ) {
+            break;
+        }
+        result.distance.insert(t, g);
+        if goal.as_mut().is_some_and( |goal| goal(t) ) {
+            bound = Some(g);
+            result.goals.push(t);
+        }
+        for (n, c) in neighbours(t) {
+            if result.distance.contains_key(&n) { continue; }
+            let g = g + c;
+            match front.entry(n) {
+                Entry::Vacant(e) => e.set_priority(Reverse(g)),
+                Entry::Occupied(e) => match e.get_priority().0.cmp(&g) {
+                    Ordering::Greater => _ = e.set_priority(Reverse(g)),
+                    Ordering::Equal => {
+                        result.predecessors.entry(n).or_default().push(t);
+                        continue;
+                    },
+                    Ordering::Less => continue
+                }
+            }
+            result.predecessors.insert(n, SmallVec::from_elem(t, 1));
+        }
+    }
+    result
+}
+
+/// explores every reachable node, recording all optimal predecessors
+#[inline]
+pub fn shortest_paths<T, X, S, N, NR>(start: S, neighbours: N) -> ShortestPaths<T, X>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>
+{
+    all_paths(start, neighbours, None::<fn(T) -> bool>)
+}
+
+/// stops once every node at the cost of the nearest goal has been settled
+#[inline]
+pub fn shortest_paths_to<T, X, S, N, NR, G>(start: S, neighbours: N, goal: G) -> ShortestPaths<T, X>
+where
+    T: Copy + Eq + Hash,
+    X: Copy + Ord + Add<X, Output = X> + Zero,
+    S: IntoIterator<Item = T>,
+    N: FnMut(T) -> NR,
+    NR: IntoIterator<Item = (T, X)>,
+    G: FnMut(T) -> bool
+{
+    all_paths(start, neighbours, Some(goal))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAZE: [&[u8]; 5] = [
+        b"S.#....",
+        b".##.##.",
+        b"....#..",
+        b"#.#.#.#",
+        b"..#...G",
+    ];
+
+    fn find(chr: u8) -> [usize; 2] {
+        MAZE.iter().enumerate()
+            .find_map( |(y, row)| row.iter().position( |c| *c == chr ).map( |x| [x, y] ) )
+            .unwrap()
+    }
+
+    fn neighbours([x, y]: [usize; 2]) -> impl Iterator<Item = [usize; 2]> {
+        [[x.wrapping_sub(1), y], [x, y.wrapping_sub(1)], [x + 1, y], [x, y + 1]].into_iter()
+            .filter( |[x, y]| MAZE.get(*y).and_then( |row| row.get(*x) ).is_some_and( |c| *c != b'#' ) )
+    }
+
+    fn is_step([ax, ay]: [usize; 2], [bx, by]: [usize; 2]) -> bool {
+        ax.abs_diff(bx) + ay.abs_diff(by) == 1
+    }
+
+    #[test]
+    fn astar_path() {
+        let (start, goal) = (find(b'S'), find(b'G'));
+        let found = astar(
+            [start],
+            |t| neighbours(t).map( |n| (n, 1u32) ),
+            |[x, y]| (goal[0].abs_diff(x) + goal[1].abs_diff(y)) as u32,
+            |t| t == goal,
+            Record::ALL
+        ).unwrap();
+        assert_eq!(found.cost, 10);
+        let path = found.path.unwrap();
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.windows(2).all( |w| is_step(w[0], w[1]) ));
+        assert!(!found.expanded.unwrap().contains(&goal));
+    }
+
+    #[test]
+    fn dijkstra_matches_bfs() {
+        let (start, goal) = (find(b'S'), find(b'G'));
+        let weighted = dijkstra(
+            [start],
+            |t| neighbours(t).map( |n| (n, 1usize) ),
+            |t| t == goal,
+            Record::NONE
+        ).unwrap();
+        let unweighted = bfs([start], neighbours, |t| t == goal, Record::PATH).unwrap();
+        assert_eq!(weighted.cost, unweighted.cost);
+        assert!(weighted.path.is_none() && weighted.expanded.is_none());
+        assert_eq!(unweighted.path.unwrap().len(), unweighted.cost + 1);
+        let dense = bfs_in(Dense([7, 5]), [start], neighbours, |t| t == goal, Record::ALL).unwrap();
+        assert_eq!(dense.cost, unweighted.cost);
+        assert_eq!(dense.path.unwrap().len(), dense.cost + 1);
+        assert!(dense.expanded.unwrap().contains(&start));
+    }
+
+    #[test]
+    fn unreachable() {
+        let found = bfs([find(b'S')], neighbours, |[x, y]| x == 3 && y == 0, Record::NONE);
+        assert!(found.is_some());
+        let found = bfs([find(b'S')], neighbours, |[x, _]| x > 6, Record::NONE);
+        assert!(found.is_none());
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        let open = |[x, y]: [usize; 2]|
+            [[x + 1, y], [x, y + 1]].into_iter()
+                .filter( |[x, y]| *x < 3 && *y < 3 )
+                .map( |n| (n, 1u32) );
+        let paths = shortest_paths([[0, 0]], open);
+        assert_eq!(paths.distances().len(), 9);
+        assert_eq!(paths.distance(&[2, 1]), Some(3));
+        assert_eq!(paths.count_paths([2, 2]), 6);
+        assert_eq!(paths.count_paths([0, 0]), 1);
+        assert_eq!(paths.on_any_path([[2, 2]]).len(), 9);
+        let all = paths.paths([2, 2]).collect::<HashSet<_>>();
+        assert_eq!(all.len(), 6);
+        assert!(all.iter().all( |path| path.len() == 5 && path[0] == [0, 0] && path[4] == [2, 2] ));
+        assert_eq!(paths.paths([0, 0]).collect::<Vec<_>>(), vec![vec![[0, 0]]]);
+    }
+
+    #[test]
+    fn all_shortest_paths_to_goal() {
+        let (start, goal) = (find(b'S'), find(b'G'));
+        let paths = shortest_paths_to([start], |t| neighbours(t).map( |n| (n, 1usize) ), |t| t == goal);
+        assert_eq!(paths.goals(), &[goal]);
+        assert_eq!(paths.distance(&goal), Some(10));
+        assert_eq!(paths.count_paths(goal), 1);
+        assert_eq!(paths.on_any_path([goal]).len(), 11);
+        assert_eq!(paths.distance(&[6, 0]), Some(10));
+        assert!(paths.distance(&[6, 1]).is_none());
+    }
+
+    #[test]
+    fn dial_matches_dijkstra() {
+        const W: usize = 8;
+        let cost = |[x, y]: [usize; 2]| (x * 7 + y * 3) % 9 + 1;
+        let neighbours = |[x, y]: [usize; 2]|
+            [[x.wrapping_sub(1), y], [x, y.wrapping_sub(1)], [x + 1, y], [x, y + 1]].into_iter()
+                .filter( |[x, y]| *x < W && *y < W )
+                .map( move |n| (n, cost(n)) );
+        for goal in [[W - 1, W - 1], [3, 5], [0, 0]] {
+            let expected = dijkstra([[0, 0]], neighbours, |t| t == goal, Record::NONE).unwrap();
+            let found = dial([[0, 0]], neighbours, |t| t == goal, 9, [W, W]);
+            assert_eq!(found, Some((goal, expected.cost)));
+        }
+        assert!(dial([[0, 0]], neighbours, |[x, _]| x == W, 9, [W, W]).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn dial_rejects_heavy_edges() {
+        let neighbours = |[x, y]: [usize; 2]| [([x + 1, y], 10)].into_iter().filter( |([x, _], _)| *x < 4 );
+        dial([[0, 0]], neighbours, |[x, _]| x == 3, 9, [4, 1]);
+    }
+}
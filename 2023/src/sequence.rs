@@ -0,0 +1,184 @@
+use num_traits::{Num, FromPrimitive};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum SequenceError {
+    #[error("no row of finite differences becomes zero")]
+    NotPolynomial,
+    #[error("sequence is too short to determine a recurrence of order {0}")]
+    NoRecurrence(usize)
+}
+
+/// Polynomial in Newton form, `p(n) = sum_k binomial(n, k) * differences[k]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial<T> {
+    differences: Vec<T>
+}
+impl<T: Num + Clone + FromPrimitive> Polynomial<T> {
+    /// fits `sequence[i] = p(i)` by repeated finite differences
+    ///
+    /// Fails if the last row of differences is not zero, so at least `degree + 2` values are needed.
+    pub fn fit(sequence: &[T]) -> Result<Self, SequenceError> {
+        let mut row = sequence.to_vec();
+        let mut differences = Vec::new();
+        while !row.iter().all(T::is_zero) {
+            differences.push(row[0].clone());
+            row = row.windows(2)
+                .map( |w| w[1].clone() - w[0].clone() )
+                .collect();
+        }
+        if row.is_empty() { return Err(SequenceError::NotPolynomial); }
+        Ok(Self { differences })
+    }
+    #[inline]
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+    /// value at any `index`, including negative ones
+    pub fn at(&self, index: i64) -> T {
+        let mut binomial = T::one();
+        let mut result = T::zero();
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                // binomial(n, k) = binomial(n, k - 1) * (n - k + 1) / k stays exact
+                let k = k as i64;
+                binomial = binomial * T::from_i64(index - k + 1).unwrap() / T::from_i64(k).unwrap();
+            }
+            result = result + binomial.clone() * difference.clone();
+        }
+        result
+    }
+}
+
+/// `sequence` extended (or continued backwards) to `index`, assuming it is polynomial
+#[inline]
+pub fn extrapolate<T: Num + Clone + FromPrimitive>(sequence: &[T], index: i64) -> Result<T, SequenceError> {
+    Polynomial::fit(sequence).map( |p| p.at(index) )
+}
+
+/// `a[n] = sum_i coefficients[i] * a[n - 1 - i]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence<T> {
+    coefficients: Vec<T>
+}
+impl<T: Num + Clone> Recurrence<T> {
+    /// shortest linear recurrence generating `sequence` (Berlekamp-Massey)
+    ///
+    /// `T` has to be a field (e.g. `Ratio`), the result is only unique when `sequence` holds
+    /// at least twice as many values as the order of the recurrence.
+    pub fn find(sequence: &[T]) -> Result<Self, SequenceError> {
+        let (mut current, mut previous) = (vec![T::one()], vec![T::one()]);
+        let (mut order, mut shift, mut last) = (0, 1, T::one());
+        for n in 0..sequence.len() {
+            let discrepancy = (1..=order)
+                .fold(sequence[n].clone(), |d, i| d + current[i].clone() * sequence[n - i].clone() );
+            if discrepancy.is_zero() {
+                shift += 1;
+                continue;
+            }
+            let factor = discrepancy.clone() / last.clone();
+            let update = |current: &mut Vec<T>| {
+                if current.len() < previous.len() + shift {
+                    current.resize(previous.len() + shift, T::zero());
+                }
+                for (i, b) in previous.iter().enumerate() {
+                    current[i + shift] = current[i + shift].clone() - factor.clone() * b.clone();
+                }
+            };
+            if 2 * order <= n {
+                let old = current.clone();
+                update(&mut current);
+                order = n + 1 - order;
+                previous = old;
+                last = discrepancy;
+                shift = 1;
+            } else {
+                update(&mut current);
+                shift += 1;
+            }
+        }
+        if 2 * order > sequence.len() { return Err(SequenceError::NoRecurrence(order)); }
+        current.resize(order + 1, T::zero());
+        let coefficients = current.into_iter().skip(1)
+            .map( |c| T::zero() - c )
+            .collect();
+        Ok(Self { coefficients })
+    }
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+    #[inline]
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+    /// value at `index`, continuing `sequence` forwards or backwards
+    ///
+    /// Going backwards needs a non-zero last coefficient.
+    pub fn at(&self, sequence: &[T], index: i64) -> Option<T> {
+        let order = self.order();
+        if let Some(value) = usize::try_from(index).ok().and_then( |i| sequence.get(i) ) {
+            return Some(value.clone());
+        }
+        if order == 0 { return Some(T::zero()); }
+        if sequence.len() < order { return None; }
+        if index >= 0 {
+            let mut window = sequence[sequence.len() - order..].to_vec();
+            for _ in sequence.len()..=index as usize {
+                let next = self.coefficients.iter().zip(window.iter().rev())
+                    .fold(T::zero(), |acc, (c, a)| acc + c.clone() * a.clone() );
+                window.remove(0);
+                window.push(next);
+            }
+            window.pop()
+        } else {
+            let last = self.coefficients[order - 1].clone();
+            if last.is_zero() { return None; }
+            let mut window = sequence[..order].to_vec();
+            for _ in index..0 {
+                // a[n] - sum_{i < order - 1} c_i a[n - 1 - i] = c_last a[n - order]
+                let rest = self.coefficients[..order - 1].iter().zip(window[..order - 1].iter().rev())
+                    .fold(window[order - 1].clone(), |acc, (c, a)| acc - c.clone() * a.clone() );
+                window.pop();
+                window.insert(0, rest / last.clone());
+            }
+            Some(window.swap_remove(0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num::rational::Ratio;
+
+    #[test]
+    fn polynomial() {
+        let squares = (0..6i64).map( |n| n * n - 3 * n + 1 ).collect::<Vec<_>>();
+        let p = Polynomial::fit(&squares).unwrap();
+        assert_eq!(p.degree(), 2);
+        assert_eq!(p.at(10), 71);
+        assert_eq!(p.at(-4), 29);
+        assert_eq!(extrapolate(&[10i128, 13, 16, 21, 30, 45], 6), Ok(68));
+        assert_eq!(extrapolate(&[10i128, 13, 16, 21, 30, 45], -1), Ok(5));
+        let halves = [0, 1, 3, 6, 10].map( |n| Ratio::new(n, 2i64) );
+        assert_eq!(extrapolate(&halves, -2), Ok(Ratio::new(1, 2)));
+        assert_eq!(extrapolate(&[1i64, 2, 4, 8, 16], 5), Err(SequenceError::NotPolynomial));
+        assert_eq!(extrapolate(&[0i64, 0, 0], 100), Ok(0));
+    }
+
+    #[test]
+    fn recurrence() {
+        let fibonacci = [1, 1, 2, 3, 5, 8, 13, 21].map(Ratio::from_integer);
+        let r = Recurrence::find(&fibonacci).unwrap();
+        assert_eq!(r.coefficients(), &[Ratio::from_integer(1), Ratio::from_integer(1)]);
+        assert_eq!(r.at(&fibonacci, 10), Some(Ratio::from_integer(89)));
+        assert_eq!(r.at(&fibonacci, -3), Some(Ratio::from_integer(-1)));
+        let powers = [3, 6, 12, 24, 48].map(Ratio::from_integer);
+        let r = Recurrence::find(&powers).unwrap();
+        assert_eq!(r.coefficients(), &[Ratio::from_integer(2)]);
+        assert_eq!(r.at(&powers, -1), Some(Ratio::new(3, 2)));
+        let short = [1, 5, 2].map(Ratio::<i64>::from_integer);
+        assert_eq!(Recurrence::find(&short), Err(SequenceError::NoRecurrence(2)));
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash
+};
+use bit_vec::BitVec;
+
+pub trait StateIndex {
+    /// runtime information needed to enumerate all states, `()` for types with a fixed count
+    type Bound: Copy;
+    fn count(bound: Self::Bound) -> usize;
+    /// unique value below `Self::count(bound)`
+    fn index(&self, bound: Self::Bound) -> usize;
+}
+impl<const N: usize> StateIndex for [usize; N] {
+    type Bound = [usize; N];
+    #[inline]
+    fn count(bound: Self::Bound) -> usize {
+        bound.iter().product()
+    }
+    #[inline]
+    fn index(&self, bound: Self::Bound) -> usize {
+        let mut result = 0;
+        for i in (0..N).rev() {
+            assert!(self[i] < bound[i], "state out of bounds");
+            result = result * bound[i] + self[i];
+        }
+        result
+    }
+}
+impl<A: StateIndex, B: StateIndex> StateIndex for (A, B) {
+    type Bound = (A::Bound, B::Bound);
+    #[inline]
+    fn count((a, b): Self::Bound) -> usize {
+        A::count(a) * B::count(b)
+    }
+    #[inline]
+    fn index(&self, (a, b): Self::Bound) -> usize {
+        self.0.index(a) * B::count(b) + self.1.index(b)
+    }
+}
+impl<A: StateIndex, B: StateIndex, C: StateIndex> StateIndex for (A, B, C) {
+    type Bound = (A::Bound, B::Bound, C::Bound);
+    #[inline]
+    fn count((a, b, c): Self::Bound) -> usize {
+        A::count(a) * B::count(b) * C::count(c)
+    }
+    #[inline]
+    fn index(&self, (a, b, c): Self::Bound) -> usize {
+        (self.0.index(a) * B::count(b) + self.1.index(b)) * C::count(c) + self.2.index(c)
+    }
+}
+
+pub trait StateSet<T> {
+    /// returns `true` when `t` was not present before
+    fn insert(&mut self, t: T) -> bool;
+    fn contains(&self, t: &T) -> bool;
+}
+impl<T: Eq + Hash> StateSet<T> for HashSet<T> {
+    #[inline(always)]
+    fn insert(&mut self, t: T) -> bool {
+        HashSet::insert(self, t)
+    }
+    #[inline(always)]
+    fn contains(&self, t: &T) -> bool {
+        HashSet::contains(self, t)
+    }
+}
+
+pub trait StateMap<T, V> {
+    fn insert(&mut self, t: T, v: V);
+    fn get(&self, t: &T) -> Option<&V>;
+}
+impl<T: Eq + Hash, V> StateMap<T, V> for HashMap<T, V> {
+    #[inline(always)]
+    fn insert(&mut self, t: T, v: V) {
+        HashMap::insert(self, t, v);
+    }
+    #[inline(always)]
+    fn get(&self, t: &T) -> Option<&V> {
+        HashMap::get(self, t)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DenseSet<T: StateIndex> {
+    bits: BitVec,
+    bound: T::Bound
+}
+impl<T: StateIndex> DenseSet<T> {
+    #[inline]
+    pub fn new(bound: T::Bound) -> Self {
+        Self { bits: BitVec::from_elem(T::count(bound), false), bound }
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bits.iter().filter( |x| *x ).count()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bits.none()
+    }
+}
+impl<T: StateIndex> StateSet<T> for DenseSet<T> {
+    #[inline]
+    fn insert(&mut self, t: T) -> bool {
+        let i = t.index(self.bound);
+        let new = !self.bits[i];
+        self.bits.set(i, true);
+        new
+    }
+    #[inline]
+    fn contains(&self, t: &T) -> bool {
+        self.bits[t.index(self.bound)]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DenseMap<T: StateIndex, V> {
+    values: Vec<Option<V>>,
+    bound: T::Bound
+}
+impl<T: StateIndex, V> DenseMap<T, V> {
+    #[inline]
+    pub fn new(bound: T::Bound) -> Self {
+        Self { values: (0..T::count(bound)).map( |_| None ).collect(), bound }
+    }
+}
+impl<T: StateIndex, V> StateMap<T, V> for DenseMap<T, V> {
+    #[inline]
+    fn insert(&mut self, t: T, v: V) {
+        self.values[t.index(self.bound)] = Some(v);
+    }
+    #[inline]
+    fn get(&self, t: &T) -> Option<&V> {
+        self.values[t.index(self.bound)].as_ref()
+    }
+}
+
+/// selects the containers used by the search helpers
+pub trait Storage<T> {
+    type Set: StateSet<T>;
+    type Map<V>: StateMap<T, V>;
+    fn set(&self) -> Self::Set;
+    fn map<V>(&self) -> Self::Map<V>;
+}
+
+/// fallback for states without a dense index
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Hashed;
+impl<T: Eq + Hash> Storage<T> for Hashed {
+    type Set = HashSet<T>;
+    type Map<V> = HashMap<T, V>;
+    #[inline(always)]
+    fn set(&self) -> Self::Set {
+        HashSet::new()
+    }
+    #[inline(always)]
+    fn map<V>(&self) -> Self::Map<V> {
+        HashMap::new()
+    }
+}
+
+/// bit- and vector-backed storage for states implementing `StateIndex`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dense<B>(pub B);
+impl<T: StateIndex> Storage<T> for Dense<T::Bound> {
+    type Set = DenseSet<T>;
+    type Map<V> = DenseMap<T, V>;
+    #[inline(always)]
+    fn set(&self) -> Self::Set {
+        DenseSet::new(self.0)
+    }
+    #[inline(always)]
+    fn map<V>(&self) -> Self::Map<V> {
+        DenseMap::new(self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Dir {
+        E,
+        N,
+        W,
+        S
+    }
+    impl StateIndex for Dir {
+        type Bound = ();
+        fn count(_: ()) -> usize {
+            4
+        }
+        fn index(&self, _: ()) -> usize {
+            *self as usize
+        }
+    }
+
+    #[test]
+    fn unique_indices() {
+        let bound = ([3, 2], ());
+        let count = <([usize; 2], Dir)>::count(bound);
+        assert_eq!(count, 24);
+        let mut seen = BitVec::from_elem(count, false);
+        for y in 0..2 {
+            for x in 0..3 {
+                for dir in [Dir::E, Dir::N, Dir::W, Dir::S] {
+                    let i = ([x, y], dir).index(bound);
+                    assert!(!seen[i]);
+                    seen.set(i, true);
+                }
+            }
+        }
+        assert!(seen.all());
+    }
+
+    #[test]
+    fn dense_set() {
+        let mut set = DenseSet::<([usize; 2], Dir)>::new(([4, 4], ()));
+        assert!(set.is_empty());
+        assert!(set.insert(([1, 2], Dir::W)));
+        assert!(!set.insert(([1, 2], Dir::W)));
+        assert!(set.contains(&([1, 2], Dir::W)));
+        assert!(!set.contains(&([2, 1], Dir::W)));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds() {
+        let set = DenseSet::<[usize; 2]>::new([4, 4]);
+        set.contains(&[4, 0]);
+    }
+}
@@ -2,50 +2,19 @@ use advent_of_code::*;
 use day4::*;
 
 fn solve(input: Input) -> DResult<impl ToString> {
-    let size = *input.data.size();
-    let mut matches = NArray::<2, Box<[u32]>>::new(size);
-    let mut update = |pos: [usize; 2], dir: Dir, index: usize| {
-        // SAFETY: assumes that other_pos is in-bounds
-        let other_pos = shift(pos, size, dir, 1).unwrap();
-        let Some(other) = linear_search(&WORD, input.data[other_pos]) else {
-            return;
-        };
-        if index + 1 == other {
-            if let Some(root) = shift(pos, size, dir.invert(), index) {
-                matches[root] |= 1 << (dir.offset() + index as u32);
-            }
-        } else if index == other + 1 {
-            if let Some(root) = shift(pos, size, dir, index) {
-                matches[root] |= 1 << (dir.invert().offset() + other as u32);
-            }
-        }
-    };
-    println!(
-        "size: {:?}, stride: {:?}",
-        input.data.size(),
-        input.data.stride()
-    );
-    for (pos, &char) in &input.data {
-        let Some(index) = linear_search(&WORD, char) else {
+    let data = &input.data;
+    let mut sum = 0;
+    for (pos, &char) in data {
+        if char != WORD[0] {
             continue;
-        };
-        if pos[0] != 0 {
-            update(pos, Dir::Left, index);
         }
-        if pos[1] != 0 {
-            if pos[0] != 0 {
-                update(pos, Dir::TopLeft, index);
-            }
-            update(pos, Dir::Top, index);
-            if pos[0] + 1 != size[0] {
-                update(pos, Dir::TopRight, index);
+        for direction in Neighbourhood::Moore.offsets() {
+            let word = data.ray(pos, direction).map(|(_, &char)| char);
+            if word.take(WORD.len()).eq(WORD) {
+                sum += 1;
             }
         }
     }
-    let mut sum = 0;
-    for (_, r#match) in &matches {
-        sum += MASK.iter().filter(|&m| r#match & m == *m).count()
-    }
     Ok(sum)
 }
 
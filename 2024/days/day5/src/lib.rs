@@ -1,6 +1,7 @@
 use core::ops::RangeInclusive;
 use std::str::FromStr;
 
+use advent_of_code::Precedence;
 use nom::{
     IResult,
     bytes::complete::take_while_m_n,
@@ -20,8 +21,8 @@ pub const PAGE_COUNT: usize = LAST_PAGE - FIRST_PAGE + 1;
 
 #[derive(Debug)]
 pub struct Input {
-    pub rules: Box<[Box<[u8]>]>,
-    pub orders: Box<[Box<[u8]>]>,
+    pub rules: Precedence,
+    pub orders: Box<[Box<[usize]>]>,
 }
 impl FromStr for Input {
     type Err = nom::Err<nom::error::Error<String>>;
@@ -35,28 +36,21 @@ impl FromStr for Input {
         fn rule(input: &str) -> IResult<&str, (u8, u8)> {
             separated_pair(page, char('|'), page)(input)
         }
-        fn order(input: &str) -> IResult<&str, Box<[u8]>> {
+        fn order(input: &str) -> IResult<&str, Box<[usize]>> {
             map(separated_list1(char(','), page), |v| {
-                v.into_iter().map(|x| x - FIRST_PAGE as u8).collect()
+                v.into_iter().map(|x| x as usize - FIRST_PAGE).collect()
             })(input)
         }
         let (s, rules) = separated_list1(line_ending, rule)(s).map_err(|e| e.to_owned())?;
         let (_, orders) = preceded(multispace0, separated_list1(line_ending, order))(s)
             .map_err(|e| e.to_owned())?;
-        let mut edges = vec![Vec::new(); PAGE_COUNT];
-        for (from, to) in rules {
+        let rules = rules.into_iter().map(|(from, to)| {
             assert!(PAGES.contains(&(from as usize)));
             assert!(PAGES.contains(&(to as usize)));
-            edges[from as usize - FIRST_PAGE].push(to - FIRST_PAGE as u8);
-        }
+            (from as usize - FIRST_PAGE, to as usize - FIRST_PAGE)
+        });
         Ok(Input {
-            rules: edges
-                .into_iter()
-                .map(|mut v| {
-                    v.sort_unstable();
-                    v.into_boxed_slice()
-                })
-                .collect(),
+            rules: Precedence::new(PAGE_COUNT, rules),
             orders: orders.into_boxed_slice(),
         })
     }
@@ -0,0 +1,225 @@
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+use num_traits::PrimInt;
+
+use crate::RangeAny;
+
+/// Set of integers stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The whole domain of `T`.
+    pub fn full() -> Self {
+        Self {
+            ranges: vec![(T::min_value(), T::max_value())],
+        }
+    }
+    fn normalize(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.sort_unstable();
+        let mut result: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            if let Some(last) = result.last_mut() {
+                // ranges ending at T::max_value() touch everything after them
+                if last
+                    .1
+                    .checked_add(&T::one())
+                    .is_none_or(|next| start <= next)
+                {
+                    last.1 = last.1.max(end);
+                    continue;
+                }
+            }
+            result.push((start, end));
+        }
+        Self { ranges: result }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+    /// Number of elements, overflows when the set covers all of `T`.
+    pub fn len(&self) -> T {
+        self.ranges.iter().fold(T::zero(), |acc, &(start, end)| {
+            acc + (end - start) + T::one()
+        })
+    }
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.ranges.partition_point(|(_, end)| end < value);
+        self.ranges.get(i).is_some_and(|(start, _)| start <= value)
+    }
+    pub fn insert(&mut self, range: impl Into<RangeAny<T>>) {
+        *self = self.union(&Self::from(range.into()));
+    }
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.ranges.iter().chain(&other.ranges).copied().collect())
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a0, a1)), Some(&(b0, b1))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a0.max(b0), a1.min(b1));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a1 < b1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+    /// Gaps between the ranges within the whole domain of `T`.
+    fn gaps(&self) -> Self {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        let mut start = Some(T::min_value());
+        for &(a, b) in &self.ranges {
+            match start {
+                Some(start) if start < a => ranges.push((start, a - T::one())),
+                _ => {}
+            }
+            start = b.checked_add(&T::one());
+        }
+        if let Some(start) = start {
+            ranges.push((start, T::max_value()));
+        }
+        Self { ranges }
+    }
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.gaps())
+    }
+    /// All values inside `bound` that are not part of the set.
+    pub fn complement(&self, bound: impl Into<RangeAny<T>>) -> Self {
+        Self::from(bound.into()).intersection(&self.gaps())
+    }
+}
+impl<T: PrimInt> From<RangeAny<T>> for IntervalSet<T> {
+    /// Stepped ranges become one entry per element, so they should be short.
+    fn from(value: RangeAny<T>) -> Self {
+        if let RangeAny::Step(range, step) = value {
+            let set = Self::from(*range);
+            if step.unsigned_abs() == 1 {
+                return set;
+            }
+            let Some(&(start, end)) = set.ranges.first() else {
+                return set;
+            };
+            let stride = T::from(step.unsigned_abs()).expect("step does not fit");
+            let count = (end - start) / stride;
+            // a negative step is aligned to the end instead of the start
+            let first = if step < 0 {
+                end - count * stride
+            } else {
+                start
+            };
+            let count = count.to_usize().expect("stepped range is too long");
+            return Self {
+                ranges: (0..=count)
+                    .map(|i| first + T::from(i).unwrap() * stride)
+                    .map(|x| (x, x))
+                    .collect(),
+            };
+        }
+        let start = match value.start_bound() {
+            Bound::Included(x) => Some(*x),
+            Bound::Excluded(x) => x.checked_add(&T::one()),
+            Bound::Unbounded => Some(T::min_value()),
+        };
+        let end = match value.end_bound() {
+            Bound::Included(x) => Some(*x),
+            Bound::Excluded(x) => x.checked_sub(&T::one()),
+            Bound::Unbounded => Some(T::max_value()),
+        };
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => Self {
+                ranges: vec![(start, end)],
+            },
+            _ => Self::new(),
+        }
+    }
+}
+impl<T: PrimInt, R: Into<RangeAny<T>>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        Self::normalize(
+            iter.into_iter()
+                .flat_map(|range| Self::from(range.into()).ranges)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merging() {
+        let set = IntervalSet::from_iter([5..8, 0..3, 2..4, 8..10, 12..13]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=3, 5..=9, 12..=12]);
+        assert_eq!(set.len(), 10);
+        assert!(set.contains(&9) && !set.contains(&4) && !set.contains(&11));
+        // adjacent ranges are merged, ranges with a gap are not
+        let mut set = IntervalSet::from(RangeAny::from(0..=4));
+        set.insert(5..=6);
+        set.insert(8);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=6, 8..=8]);
+        set.insert(7);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=8]);
+        assert!(IntervalSet::from_iter([3..3, 7..7]).is_empty());
+    }
+
+    #[test]
+    fn domain_ends() {
+        let set = IntervalSet::<u8>::from_iter([RangeAny::from(250..), RangeAny::from(..=3)]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=3, 250..=255]);
+        assert_eq!(
+            set.union(&IntervalSet::from(RangeAny::from(4..250))),
+            IntervalSet::full()
+        );
+        let set = IntervalSet::<i8>::from_iter([RangeAny::from(100..), RangeAny::from(120..=127)]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [100..=127]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = IntervalSet::from_iter([0..10, 20..30]);
+        let b = IntervalSet::from(RangeAny::from(5..25));
+        assert_eq!(a.intersection(&b), IntervalSet::from_iter([5..10, 20..25]));
+        assert_eq!(a.difference(&b), IntervalSet::from_iter([0..5, 25..30]));
+        assert_eq!(a.union(&b), IntervalSet::from(RangeAny::from(0..30)));
+        assert_eq!(
+            a.complement(-5..35),
+            IntervalSet::from_iter([-5..0, 10..20, 30..35])
+        );
+        assert_eq!(
+            IntervalSet::<i32>::new().complement(..),
+            IntervalSet::full()
+        );
+    }
+
+    #[test]
+    fn stepped() {
+        let set = IntervalSet::from(RangeAny::from(0..10).step_by(3));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0..=0, 3..=3, 6..=6, 9..=9]);
+        // negative steps start at the end
+        let set = IntervalSet::from(RangeAny::from(0..10).step_by(-4));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=1, 5..=5, 9..=9]);
+        assert_eq!(
+            IntervalSet::from(RangeAny::from(2..6).rev()),
+            IntervalSet::from(RangeAny::from(2..6))
+        );
+        assert!(IntervalSet::from(RangeAny::from(4..4).step_by(2)).is_empty());
+    }
+}
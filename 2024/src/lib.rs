@@ -6,8 +6,12 @@
 
 mod range;
 pub use range::*;
+mod interval;
+pub use interval::*;
 mod narray;
 pub use narray::*;
+mod order;
+pub use order::*;
 
 use std::io::{stdin, Read};
 
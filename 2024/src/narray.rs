@@ -1,11 +1,31 @@
 use std::{
     hint::black_box,
+    iter,
+    marker::PhantomData,
     mem::MaybeUninit,
-    ops::{Deref, DerefMut, Index, IndexMut, RangeInclusive},
+    ops::{Bound, Index, IndexMut, RangeBounds},
 };
 
 use crate::RangeAny;
 
+mod lanes;
+pub use lanes::*;
+mod neighbours;
+pub use neighbours::*;
+mod ops;
+pub use ops::*;
+mod parallel;
+mod stencil;
+pub use stencil::*;
+mod storage;
+pub use storage::*;
+mod text;
+pub use text::*;
+mod transform;
+
+/// Element type of an `NArray` over the container `D`.
+pub type Element<D> = <D as Storage>::Elem;
+
 const fn any<const N: usize>(vec: &[usize; N], val: usize) -> bool {
     let mut i = 0;
     while i < N {
@@ -25,35 +45,78 @@ const fn prod<const N: usize>(vec: &[usize; N]) -> usize {
     }
     result
 }
-const fn default_stride<const N: usize>(size: &[usize; N]) -> [usize; N] {
+const fn default_stride<const N: usize>(size: &[usize; N]) -> [isize; N] {
     let mut result = [1; N];
     let mut i = 1;
     while i < N {
-        result[i] = result[i - 1] * size[i - 1];
+        result[i] = result[i - 1] * size[i - 1] as isize;
         i += 1;
     }
     result
 }
-const fn linear<const N: usize>(index: [usize; N], stride: &[usize; N]) -> usize {
+const fn linear<const N: usize>(index: [usize; N], stride: &[isize; N]) -> isize {
     let mut result = 0;
     let mut i = 0;
     while i < N {
-        result += index[i] * stride[i];
+        result += index[i] as isize * stride[i];
         i += 1;
     }
     result
 }
-const fn last<const N: usize>(size: &[usize; N], stride: &[usize; N]) -> Option<usize> {
-    let mut index = [0; N];
+/// Buffer index of `index` in an array starting at `offset`.
+const fn address<const N: usize>(offset: usize, index: [usize; N], stride: &[isize; N]) -> usize {
+    offset.wrapping_add_signed(linear(index, stride))
+}
+/// Smallest and largest value of `linear` over all indices, `None` for empty arrays.
+const fn extent<const N: usize>(size: &[usize; N], stride: &[isize; N]) -> Option<(isize, isize)> {
+    let (mut min, mut max) = (0, 0);
     let mut i = 0;
     while i < N {
-        let Some(j) = size[i].checked_sub(1) else {
+        if size[i] == 0 {
             return None;
-        };
-        index[i] = j;
+        }
+        let end = (size[i] - 1) as isize * stride[i];
+        if end < 0 {
+            min += end;
+        } else {
+            max += end;
+        }
         i += 1;
     }
-    Some(linear(index, stride))
+    Some((min, max))
+}
+/// Panics if any element of the array would lie outside of `data`.
+fn check_bounds<const N: usize, T>(
+    data: &[T],
+    size: &[usize; N],
+    stride: &[isize; N],
+    offset: usize,
+) {
+    if let Some((min, max)) = extent(size, stride) {
+        let first = offset
+            .checked_add_signed(min)
+            .expect("array starts before the buffer");
+        black_box(&data[first]);
+        black_box(&data[offset.wrapping_add_signed(max)]);
+    }
+}
+/// Whether every position refers to a different element, i.e. no stride is covered by the smaller ones.
+fn is_disjoint<const N: usize>(size: &[usize; N], stride: &[isize; N]) -> bool {
+    if prod(size) == 0 {
+        return true;
+    }
+    let mut axes: [usize; N] = std::array::from_fn(|i| i);
+    axes.sort_by_key(|&i| stride[i].unsigned_abs());
+    let mut extent = 0;
+    for i in axes {
+        if size[i] > 1 {
+            if stride[i].unsigned_abs() <= extent {
+                return false;
+            }
+            extent += (size[i] - 1) * stride[i].unsigned_abs();
+        }
+    }
+    true
 }
 const fn next<const N: usize>(index: &mut [usize; N], size: &[usize; N]) {
     if N == 0 {
@@ -71,7 +134,7 @@ const fn next<const N: usize>(index: &mut [usize; N], size: &[usize; N]) {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NArray<const N: usize, D> {
     size: [usize; N],
-    stride: [usize; N],
+    stride: [isize; N],
     offset: usize,
     data: D,
 }
@@ -91,7 +154,7 @@ impl<const N: usize, D> NArray<N, D> {
     pub unsafe fn from_buffer_with_stride_unchecked(
         data: D,
         size: [usize; N],
-        stride: [usize; N],
+        stride: [isize; N],
     ) -> Self {
         Self {
             size,
@@ -105,7 +168,7 @@ impl<const N: usize, D> NArray<N, D> {
     pub unsafe fn from_buffer_with_stride_and_offset_unchecked(
         data: D,
         size: [usize; N],
-        stride: [usize; N],
+        stride: [isize; N],
         offset: usize,
     ) -> Self {
         Self {
@@ -118,7 +181,7 @@ impl<const N: usize, D> NArray<N, D> {
     pub const fn size(&self) -> &[usize; N] {
         &self.size
     }
-    pub const fn stride(&self) -> &[usize; N] {
+    pub const fn stride(&self) -> &[isize; N] {
         &self.stride
     }
     pub const fn offset(&self) -> usize {
@@ -131,12 +194,10 @@ impl<const N: usize, D> NArray<N, D> {
         any(&self.size, 0)
     }
 }
-impl<const N: usize, D: Deref<Target: Index<usize>>> NArray<N, D> {
+impl<const N: usize, D: Storage> NArray<N, D> {
     pub fn from_buffer(data: D, size: [usize; N]) -> Self {
         let stride = default_stride(&size);
-        if let Some(last) = last(&size, &stride) {
-            black_box(&data[last]);
-        }
+        check_bounds(data.as_slice(), &size, &stride, 0);
         Self {
             size,
             stride,
@@ -144,10 +205,8 @@ impl<const N: usize, D: Deref<Target: Index<usize>>> NArray<N, D> {
             data,
         }
     }
-    pub fn from_buffer_with_stride(data: D, size: [usize; N], stride: [usize; N]) -> Self {
-        if let Some(last) = last(&size, &stride) {
-            black_box(&data[last]);
-        }
+    pub fn from_buffer_with_stride(data: D, size: [usize; N], stride: [isize; N]) -> Self {
+        check_bounds(data.as_slice(), &size, &stride, 0);
         Self {
             size,
             stride,
@@ -158,61 +217,99 @@ impl<const N: usize, D: Deref<Target: Index<usize>>> NArray<N, D> {
     pub fn from_buffer_with_stride_and_offset(
         data: D,
         size: [usize; N],
-        stride: [usize; N],
+        stride: [isize; N],
         offset: usize,
     ) -> Self {
-        if let Some(last) = last(&size, &stride) {
-            black_box(&data[offset + last]);
-        }
+        check_bounds(data.as_slice(), &size, &stride, offset);
         Self {
             size,
             stride,
-            offset: 0,
+            offset,
             data,
         }
     }
 }
-// TODO: generalize this for other containers
-impl<const N: usize, T: Default> NArray<N, Box<[T]>> {
+impl<const N: usize, D: OwnedStorage> NArray<N, D> {
+    /// Takes the elements in memory order, `None` if `iter` is too short or `D` can't hold them.
+    pub fn from_iter(size: [usize; N], iter: impl IntoIterator<Item = D::Elem>) -> Option<Self> {
+        let data = D::from_elements(prod(&size), iter.into_iter())?;
+        // SAFETY: data holds exactly as many elements as size requires
+        Some(unsafe { Self::from_buffer_unchecked(data, size) })
+    }
+    /// Fills the array with `f(pos)`, visiting positions in memory order.
+    ///
+    /// # Panics
+    /// If `D` has a fixed length other than the number of elements.
+    pub fn from_fn(size: [usize; N], mut f: impl FnMut([usize; N]) -> D::Elem) -> Self {
+        let mut pos = [0; N];
+        let elements = (0..prod(&size)).map(|_| {
+            let value = f(pos);
+            next(&mut pos, &size);
+            value
+        });
+        Self::from_iter(size, elements).expect("storage does not fit the size")
+    }
+}
+impl<const N: usize, T: Clone, D: OwnedStorage<Elem = T>> NArray<N, D> {
+    pub fn filled(size: [usize; N], value: T) -> Self {
+        Self::from_iter(size, iter::repeat_n(value, prod(&size)))
+            .expect("storage does not fit the size")
+    }
+}
+impl<const N: usize, T: Default, D: OwnedStorage<Elem = T>> NArray<N, D> {
     pub fn new(size: [usize; N]) -> Self {
-        let data = (0..prod(&size)).map(|_| T::default()).collect();
-        // SAFETY: data is big enough by construction
-        unsafe { Self::from_buffer_unchecked(data, size) }
+        Self::from_fn(size, |_| T::default())
+    }
+}
+impl<const N: usize, T: Clone, D: StorageMut<Elem = T>> NArray<N, D> {
+    pub fn fill(&mut self, value: T) {
+        self.fill_with(|_| value.clone());
+    }
+}
+impl<const N: usize, D: StorageMut> NArray<N, D> {
+    /// Overwrites every element with `f(pos)`, also for borrowed storage.
+    pub fn fill_with(&mut self, mut f: impl FnMut([usize; N]) -> D::Elem) {
+        for (pos, value) in self {
+            *value = f(pos);
+        }
     }
 }
-impl<const N: usize, D: Deref<Target: Index<usize>>> Index<[usize; N]> for NArray<N, D> {
-    type Output = <<D as Deref>::Target as Index<usize>>::Output;
+impl<const N: usize, D: Storage> Index<[usize; N]> for NArray<N, D> {
+    type Output = D::Elem;
 
     fn index(&self, index: [usize; N]) -> &Self::Output {
-        &self.data[self.offset + linear(index, &self.stride)]
+        assert!(self.in_bounds(index), "index out of bounds");
+        &self.data.as_slice()[address(self.offset, index, &self.stride)]
     }
 }
-impl<const N: usize, D: DerefMut<Target: IndexMut<usize>>> IndexMut<[usize; N]> for NArray<N, D> {
+impl<const N: usize, D: StorageMut> IndexMut<[usize; N]> for NArray<N, D> {
     fn index_mut(&mut self, index: [usize; N]) -> &mut Self::Output {
-        &mut self.data[self.offset + linear(index, &self.stride)]
+        assert!(self.in_bounds(index), "index out of bounds");
+        &mut self.data.as_mut_slice()[address(self.offset, index, &self.stride)]
     }
 }
 
-pub struct Iter<'a, const N: usize, D: Deref<Target: Index<usize>>> {
+pub struct Iter<'a, const N: usize, D: Storage> {
     data: &'a NArray<N, D>,
     current: [usize; N],
+    remaining: usize,
 }
-impl<'a, const N: usize, D: Deref<Target: Index<usize>>> Iterator for Iter<'a, N, D> {
-    type Item = (
-        [usize; N],
-        &'a <<D as Deref>::Target as Index<usize>>::Output,
-    );
+impl<'a, const N: usize, D: Storage> Iterator for Iter<'a, N, D> {
+    type Item = ([usize; N], &'a Element<D>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current[N - 1] >= self.data.size()[N - 1] {
-            return None;
-        }
+        // counting instead of checking the last index also covers N == 0 and empty arrays
+        self.remaining = self.remaining.checked_sub(1)?;
         let result = (self.current, &self.data[self.current]);
         next(&mut self.current, self.data.size());
         Some(result)
     }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
-impl<'a, const N: usize, D: Deref<Target: Index<usize>>> IntoIterator for &'a NArray<N, D> {
+impl<const N: usize, D: Storage> ExactSizeIterator for Iter<'_, N, D> {}
+impl<'a, const N: usize, D: Storage> IntoIterator for &'a NArray<N, D> {
     type IntoIter = Iter<'a, N, D>;
     type Item = <Self::IntoIter as Iterator>::Item;
 
@@ -220,71 +317,167 @@ impl<'a, const N: usize, D: Deref<Target: Index<usize>>> IntoIterator for &'a NA
         Iter {
             data: self,
             current: [0; N],
+            remaining: self.len(),
         }
     }
 }
-impl<const N: usize, D: Deref<Target: Index<usize>>> NArray<N, D> {
-    pub fn iter(&self) -> Iter<N, D> {
-        Iter {
-            data: self,
-            current: [0; N],
-        }
+impl<const N: usize, D: Storage> NArray<N, D> {
+    pub fn iter(&self) -> Iter<'_, N, D> {
+        self.into_iter()
     }
 }
-pub struct IterMut<'a, const N: usize, D: DerefMut<Target: IndexMut<usize>>> {
-    data: &'a mut NArray<N, D>,
+pub struct IterMut<'a, const N: usize, T> {
+    data: *mut T,
+    size: [usize; N],
+    stride: [isize; N],
+    offset: usize,
     current: [usize; N],
+    remaining: usize,
+    marker: PhantomData<&'a mut [T]>,
+}
+// SAFETY: every element is only handed out once, just like `std::slice::IterMut`
+unsafe impl<const N: usize, T: Send> Send for IterMut<'_, N, T> {}
+unsafe impl<const N: usize, T: Sync> Sync for IterMut<'_, N, T> {}
+impl<const N: usize, T> IterMut<'_, N, T> {
+    /// # Safety
+    /// `data` has to be valid for all elements of the array, which may not alias each other.
+    unsafe fn new(data: *mut T, size: [usize; N], stride: [isize; N], offset: usize) -> Self {
+        Self {
+            data,
+            size,
+            stride,
+            offset,
+            current: [0; N],
+            remaining: prod(&size),
+            marker: PhantomData,
+        }
+    }
 }
-impl<'a, const N: usize, D: DerefMut<Target: IndexMut<usize>>> Iterator for IterMut<'a, N, D> {
-    type Item = (
-        [usize; N],
-        &'a mut <<D as Deref>::Target as Index<usize>>::Output,
-    );
+impl<'a, const N: usize, T> Iterator for IterMut<'a, N, T> {
+    type Item = ([usize; N], &'a mut T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current[N - 1] >= self.data.size()[N - 1] {
-            return None;
-        }
-        // SAFETY: value will not be accessed anywhere else
-        let value = unsafe {
-            (&mut self.data[self.current] as *mut <<D as Deref>::Target as Index<usize>>::Output)
-                .as_mut()
-                .unwrap()
-        };
+        self.remaining = self.remaining.checked_sub(1)?;
+        let i = address(self.offset, self.current, &self.stride);
+        // SAFETY: in bounds, and no other position refers to the same element
+        let value = unsafe { &mut *self.data.add(i) };
         let result = (self.current, value);
-        next(&mut self.current, self.data.size());
+        next(&mut self.current, &self.size);
         Some(result)
     }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
-impl<'a, const N: usize, D: DerefMut<Target: IndexMut<usize>>> IntoIterator
-    for &'a mut NArray<N, D>
-{
-    type IntoIter = IterMut<'a, N, D>;
+impl<const N: usize, T> ExactSizeIterator for IterMut<'_, N, T> {}
+impl<'a, const N: usize, D: StorageMut> IntoIterator for &'a mut NArray<N, D> {
+    type IntoIter = IterMut<'a, N, D::Elem>;
     type Item = <Self::IntoIter as Iterator>::Item;
 
     fn into_iter(self) -> Self::IntoIter {
-        IterMut {
-            data: self,
-            current: [0; N],
-        }
+        self.as_view_mut().into_raw().into_iter()
     }
 }
-impl<const N: usize, D: DerefMut<Target: IndexMut<usize>>> NArray<N, D> {
-    pub fn iter_mut(&mut self) -> Iter<N, D> {
-        Iter {
-            data: self,
-            current: [0; N],
+impl<const N: usize, D: StorageMut> NArray<N, D> {
+    /// # Panics
+    /// If two positions share an element, e.g. because of a zero stride.
+    pub fn iter_mut(&mut self) -> IterMut<'_, N, D::Elem> {
+        self.into_iter()
+    }
+}
+
+impl<'a, const N: usize, T> NArray<N, &'a mut [T]> {
+    /// Same view, but only borrowing single elements, so that disjoint parts can be handed out.
+    ///
+    /// # Panics
+    /// If two positions share an element.
+    fn into_raw(self) -> NArray<N, RawMut<'a, T>> {
+        assert!(
+            is_disjoint(&self.size, &self.stride),
+            "elements of a mutable view alias each other"
+        );
+        NArray {
+            size: self.size,
+            stride: self.stride,
+            offset: self.offset,
+            data: RawMut::new(self.data),
         }
     }
 }
+impl<const N: usize, T> Index<[usize; N]> for NArray<N, RawMut<'_, T>> {
+    type Output = T;
+
+    fn index(&self, index: [usize; N]) -> &T {
+        assert!(self.in_bounds(index), "index out of bounds");
+        let i = address(self.offset, index, &self.stride);
+        // SAFETY: the view is inside the buffer and no other view reaches this element
+        unsafe { &*self.data.as_ptr().add(i) }
+    }
+}
+impl<const N: usize, T> IndexMut<[usize; N]> for NArray<N, RawMut<'_, T>> {
+    fn index_mut(&mut self, index: [usize; N]) -> &mut T {
+        assert!(self.in_bounds(index), "index out of bounds");
+        let i = address(self.offset, index, &self.stride);
+        // SAFETY: as for `index`
+        unsafe { &mut *self.data.as_ptr().add(i) }
+    }
+}
+impl<'a, const N: usize, T> IntoIterator for NArray<N, RawMut<'a, T>> {
+    type IntoIter = IterMut<'a, N, T>;
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // SAFETY: only built from disjoint views by `into_raw` and their disjoint parts
+        unsafe { IterMut::new(self.data.as_ptr(), self.size, self.stride, self.offset) }
+    }
+}
+impl<'a, const N: usize, T> IntoIterator for &'a mut NArray<N, RawMut<'_, T>> {
+    type IntoIter = IterMut<'a, N, T>;
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // SAFETY: as above, borrowing `self` for the lifetime of the iterator
+        unsafe { IterMut::new(self.data.as_ptr(), self.size, self.stride, self.offset) }
+    }
+}
+impl<const N: usize, T> NArray<N, RawMut<'_, T>> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, N, T> {
+        self.into_iter()
+    }
+}
+
+/// `(first, count, step)` of the indices selected by `range`, `size` is needed for unbounded ends.
+fn progression(range: &RangeAny<usize>, size: Option<usize>) -> Option<(usize, usize, isize)> {
+    if let RangeAny::Step(range, step) = range {
+        if *step == 0 {
+            return None;
+        }
+        let (first, count, inner) = progression(range, size)?;
+        let n = step.unsigned_abs();
+        return Some(if *step < 0 {
+            let last = first.wrapping_add_signed(count.saturating_sub(1) as isize * inner);
+            (last, count.div_ceil(n), -inner * n as isize)
+        } else {
+            (first, count.div_ceil(n), inner * n as isize)
+        });
+    }
+    let start = range.start().unwrap_or(0);
+    let end = match range.end_bound() {
+        Bound::Included(x) => x.checked_add(1)?,
+        Bound::Excluded(x) => *x,
+        Bound::Unbounded => size?,
+    };
+    Some((start, end.saturating_sub(start), 1))
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Projector<const N: usize, const M: usize> {
-    ranges: [(usize, RangeInclusive<usize>); M],
+    /// `(axis, first, count, step)` of every remaining axis.
+    ranges: [(usize, usize, usize, isize); M],
     pins: Box<[(usize, usize)]>,
 }
 impl<const N: usize, const M: usize> Projector<N, M> {
-    pub fn new(desc: &[RangeAny<usize>; N]) -> Option<Self> {
+    fn build(desc: &[RangeAny<usize>; N], size: Option<&[usize; N]>) -> Option<Self> {
         let mut ranges = MaybeUninit::uninit_array();
         let mut pins = Vec::with_capacity(N - M);
         let mut range_count = 0;
@@ -301,9 +494,8 @@ impl<const N: usize, const M: usize> Projector<N, M> {
                     if range_count >= M {
                         return None;
                     }
-                    let start = range.start().unwrap_or(0);
-                    let end = range.end()?;
-                    ranges[range_count].write((i, start..=end));
+                    let (first, count, step) = progression(range, size.map(|size| size[i]))?;
+                    ranges[range_count].write((i, first, count, step));
                     range_count += 1;
                 }
             }
@@ -315,39 +507,12 @@ impl<const N: usize, const M: usize> Projector<N, M> {
             pins: pins.into_boxed_slice(),
         })
     }
+    /// `None` if the number of pins is not `N - M`, a step is zero or a range is unbounded at the end.
+    pub fn new(desc: &[RangeAny<usize>; N]) -> Option<Self> {
+        Self::build(desc, None)
+    }
     pub fn new_with_size(desc: &[RangeAny<usize>; N], size: &[usize; N]) -> Option<Self> {
-        let mut ranges = MaybeUninit::uninit_array();
-        let mut pins = Vec::with_capacity(N - M);
-        let mut range_count = 0;
-        let mut i = 0;
-        while i < N {
-            match &desc[i] {
-                RangeAny::Single(pin) => {
-                    if pins.len() >= N - M {
-                        return None;
-                    }
-                    pins.push((i, *pin));
-                }
-                range => {
-                    if range_count >= M {
-                        return None;
-                    }
-                    let start = range.start().unwrap_or(0);
-                    let end = match range.end() {
-                        Some(x) => x,
-                        None => size[i].saturating_sub(1),
-                    };
-                    ranges[range_count].write((i, start..=end));
-                    range_count += 1;
-                }
-            }
-            i += 1;
-        }
-        // SAFETY: pin_count == P == N - M and range_count == M here (would have returned early otherwise)
-        Some(Self {
-            ranges: unsafe { MaybeUninit::array_assume_init(ranges) },
-            pins: pins.into_boxed_slice(),
-        })
+        Self::build(desc, Some(size))
     }
     pub const fn pin(&mut self, pins: &[usize]) {
         let mut i = 0;
@@ -361,7 +526,7 @@ impl<const N: usize, const M: usize> Projector<N, M> {
 const fn project<const N: usize, const M: usize, D>(
     array: &NArray<N, D>,
     proj: &Projector<N, M>,
-) -> Option<([usize; M], [usize; M], usize)> {
+) -> Option<([usize; M], [isize; M], usize)> {
     let mut offset = 0;
     let mut i = 0;
     while i < N - M {
@@ -369,62 +534,83 @@ const fn project<const N: usize, const M: usize, D>(
         if index >= array.size[dim] {
             return None;
         }
-        offset += array.stride[dim] * index;
+        offset += array.stride[dim] * index as isize;
         i += 1;
     }
     let mut size = [0; M];
     let mut stride = [0; M];
     i = 0;
     while i < M {
-        let (dim, range) = &proj.ranges[i];
-        if *range.end() >= array.size[*dim] {
-            return None;
+        let (dim, first, count, step) = proj.ranges[i];
+        if count > 0 {
+            // both ends have to be inside, everything in between is then as well
+            let last = first as isize + (count - 1) as isize * step;
+            if first >= array.size[dim] || last < 0 || last as usize >= array.size[dim] {
+                return None;
+            }
+            offset += array.stride[dim] * first as isize;
         }
-        stride[i] = array.stride[*dim];
-        offset += stride[i] * *range.start();
-        size[i] = *range.end() - *range.start() + 1;
+        stride[i] = array.stride[dim] * step;
+        size[i] = count;
         i += 1;
     }
-    Some((size, stride, offset))
+    Some((size, stride, array.offset.wrapping_add_signed(offset)))
 }
-impl<const N: usize, D: Deref> NArray<N, D> {
-    pub fn project<const M: usize>(&self, proj: &Projector<N, M>) -> Option<NArray<M, &D::Target>> {
+impl<const N: usize, D: Storage> NArray<N, D> {
+    pub fn project<const M: usize>(&self, proj: &Projector<N, M>) -> Option<NArray<M, &[D::Elem]>> {
         let (size, stride, offset) = project(self, proj)?;
         Some(NArray {
             size,
             stride,
             offset,
-            data: self.data.deref(),
+            data: self.data.as_slice(),
         })
     }
     pub fn view<const M: usize>(
         &self,
         desc: &[RangeAny<usize>; N],
-    ) -> Option<NArray<M, &D::Target>> {
+    ) -> Option<NArray<M, &[D::Elem]>> {
         let proj = Projector::<N, M>::new_with_size(desc, self.size())?;
         self.project(&proj)
     }
+    /// View of the whole array borrowing the container.
+    pub fn as_view(&self) -> NArray<N, &[D::Elem]> {
+        NArray {
+            size: self.size,
+            stride: self.stride,
+            offset: self.offset,
+            data: self.data.as_slice(),
+        }
+    }
 }
-impl<const N: usize, D: DerefMut> NArray<N, D> {
+impl<const N: usize, D: StorageMut> NArray<N, D> {
     pub fn project_mut<const M: usize>(
         &mut self,
         proj: &Projector<N, M>,
-    ) -> Option<NArray<M, &mut D::Target>> {
+    ) -> Option<NArray<M, &mut [D::Elem]>> {
         let (size, stride, offset) = project(self, proj)?;
         Some(NArray {
             size,
             stride,
             offset,
-            data: self.data.deref_mut(),
+            data: self.data.as_mut_slice(),
         })
     }
     pub fn view_mut<const M: usize>(
         &mut self,
         desc: &[RangeAny<usize>; N],
-    ) -> Option<NArray<M, &mut D::Target>> {
+    ) -> Option<NArray<M, &mut [D::Elem]>> {
         let proj = Projector::<N, M>::new_with_size(desc, self.size())?;
         self.project_mut(&proj)
     }
+    pub fn as_view_mut(&mut self) -> NArray<N, &mut [D::Elem]> {
+        NArray {
+            size: self.size,
+            stride: self.stride,
+            offset: self.offset,
+            data: self.data.as_mut_slice(),
+        }
+    }
 }
 #[macro_export]
 macro_rules! view {
@@ -438,3 +624,98 @@ macro_rules! view_mut {
         $array.view_mut(&[ $( RangeAny::from($arg) ),+ ])
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RangeStep;
+
+    /// Test element at `[x, y]`, the digits of `x + 10 * y` spell out the position.
+    pub(crate) fn value<T: From<u8>>([x, y]: [usize; 2]) -> T {
+        T::from((x + 10 * y) as u8)
+    }
+    /// Test grid shared by the `narray` modules, filled with [`value`].
+    pub(crate) fn grid<T: From<u8>>(size: [usize; 2]) -> NArray<2, Box<[T]>> {
+        NArray::from_fn(size, value)
+    }
+    fn values<D: Storage<Elem = usize>>(array: &NArray<1, D>) -> Vec<usize> {
+        array.iter().map(|(_, x)| *x).collect()
+    }
+
+    #[test]
+    fn negative_strides() {
+        let data: Box<[usize]> = (0..10).collect();
+        let a = NArray::from_buffer_with_stride_and_offset(data.clone(), [3], [-2], 9);
+        assert_eq!(values(&a), [9, 7, 5]);
+        let a = NArray::from_buffer_with_stride_and_offset(data.clone(), [2, 2], [-1, -5], 6);
+        assert_eq!(a[[1, 1]], 0);
+        assert!(
+            std::panic::catch_unwind(|| {
+                NArray::from_buffer_with_stride(data.clone(), [2, 2], [-1, 2])
+            })
+            .is_err()
+        );
+        assert!(
+            std::panic::catch_unwind(|| {
+                NArray::from_buffer_with_stride_and_offset(data.clone(), [3], [-2], 3)
+            })
+            .is_err()
+        );
+        // the positive end is checked as well
+        assert!(
+            std::panic::catch_unwind(|| {
+                NArray::from_buffer_with_stride_and_offset(data.clone(), [2, 3], [-1, 4], 2)
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    #[should_panic = "index out of bounds"]
+    fn index_checks_each_axis() {
+        // [5, 0] would still be inside the buffer
+        let _ = grid::<usize>([4, 5])[[5, 0]];
+    }
+
+    #[test]
+    fn stepped_views() {
+        let g = grid([4, 5]);
+        let row: NArray<1, _> = view![g, (..).rev(), 3].unwrap();
+        assert_eq!(values(&row), [33, 32, 31, 30]);
+        let row: NArray<1, _> = view![g, (0..4).rev(), 2].unwrap();
+        assert_eq!(values(&row), [23, 22, 21, 20]);
+        let row: NArray<1, _> = view![g, (..).step_by(2), 0].unwrap();
+        assert_eq!(values(&row), [0, 2]);
+        let row: NArray<1, _> = view![g, (..).step_by(-3), 0].unwrap();
+        assert_eq!(values(&row), [3, 0]);
+        let row: NArray<1, _> = view![g, (0..4).step_by(3).rev(), 0].unwrap();
+        assert_eq!(values(&row), [3, 0]);
+        let sub: NArray<2, _> = view![g, (1..4).step_by(2), (..).rev()].unwrap();
+        assert_eq!((sub[[0, 0]], sub[[1, 4]]), (41, 3));
+        let empty: NArray<1, _> = view![g, (2..2).rev(), 0].unwrap();
+        assert!(empty.is_empty());
+        let invalid: [Option<NArray<1, _>>; 3] = [
+            view![g, (..5).rev(), 0],
+            view![g, (..).step_by(0), 0],
+            view![g, .., 5],
+        ];
+        assert!(invalid.iter().all(Option::is_none));
+        // views of flipped arrays
+        let flipped = g.flip(1);
+        let column: NArray<1, _> = view![flipped, 3, (..).rev()].unwrap();
+        assert_eq!(values(&column), [3, 13, 23, 33, 43]);
+        let column: NArray<1, _> = view![flipped, 3, RangeAny::from(1..).step_by(2)].unwrap();
+        assert_eq!(values(&column), [33, 13]);
+    }
+
+    #[test]
+    fn diagonals() {
+        let mut g = grid([4, 5]);
+        assert_eq!(values(&g.diagonal()), [0, 11, 22, 33]);
+        assert_eq!(values(&g.flip(0).into_diagonal()), [3, 12, 21, 30]);
+        for (_, x) in &mut g.diagonal_mut() {
+            *x = 0;
+        }
+        assert_eq!((g[[1, 1]], g[[2, 1]]), (0, 12));
+    }
+}
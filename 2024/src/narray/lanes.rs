@@ -0,0 +1,356 @@
+use std::{iter::Enumerate, ops::Range};
+
+use super::{NArray, RawMut, Storage, StorageMut, address, next, prod};
+
+/// Copy of `array` without the entry at `axis`, `M` has to be `N - 1`.
+pub(super) fn remove_axis<const N: usize, const M: usize, T: Copy + Default>(
+    array: &[T; N],
+    axis: usize,
+) -> [T; M] {
+    assert!(M + 1 == N && axis < N, "invalid axis");
+    let mut result = [T::default(); M];
+    result[..axis].copy_from_slice(&array[..axis]);
+    result[axis..].copy_from_slice(&array[axis + 1..]);
+    result
+}
+
+/// Iterator over all one-dimensional lanes along an axis, yielding the position of each lane start.
+pub struct Lanes<'a, const N: usize, T> {
+    view: NArray<N, &'a [T]>,
+    axis: usize,
+    outer: [usize; N],
+    current: [usize; N],
+    remaining: usize,
+}
+impl<'a, const N: usize, T> Lanes<'a, N, T> {
+    pub(super) fn new(view: NArray<N, &'a [T]>, axis: usize) -> Self {
+        assert!(axis < N, "invalid axis");
+        let mut outer = view.size;
+        outer[axis] = 1;
+        Self {
+            view,
+            axis,
+            outer,
+            current: [0; N],
+            remaining: prod(&outer),
+        }
+    }
+}
+impl<'a, const N: usize, T> Iterator for Lanes<'a, N, T> {
+    type Item = ([usize; N], NArray<1, &'a [T]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let pos = self.current;
+        next(&mut self.current, &self.outer);
+        let lane = NArray {
+            size: [self.view.size[self.axis]],
+            stride: [self.view.stride[self.axis]],
+            offset: address(self.view.offset, pos, &self.view.stride),
+            data: self.view.data,
+        };
+        Some((pos, lane))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<const N: usize, T> ExactSizeIterator for Lanes<'_, N, T> {}
+
+pub struct LanesMut<'a, const N: usize, T> {
+    view: NArray<N, RawMut<'a, T>>,
+    axis: usize,
+    outer: [usize; N],
+    current: [usize; N],
+    remaining: usize,
+}
+impl<'a, const N: usize, T> LanesMut<'a, N, T> {
+    fn new(view: NArray<N, &'a mut [T]>, axis: usize) -> Self {
+        assert!(axis < N, "invalid axis");
+        let view = view.into_raw();
+        let mut outer = view.size;
+        outer[axis] = 1;
+        Self {
+            view,
+            axis,
+            outer,
+            current: [0; N],
+            remaining: prod(&outer),
+        }
+    }
+}
+impl<'a, const N: usize, T> Iterator for LanesMut<'a, N, T> {
+    type Item = ([usize; N], NArray<1, RawMut<'a, T>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let pos = self.current;
+        next(&mut self.current, &self.outer);
+        let lane = NArray {
+            size: [self.view.size[self.axis]],
+            stride: [self.view.stride[self.axis]],
+            offset: address(self.view.offset, pos, &self.view.stride),
+            // SAFETY: lanes along the same axis of a disjoint view never share an element
+            data: unsafe { self.view.data.alias() },
+        };
+        Some((pos, lane))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<const N: usize, T> ExactSizeIterator for LanesMut<'_, N, T> {}
+
+/// Iterator over the `M`-dimensional slices of an array orthogonal to an axis.
+pub struct AxisIter<'a, const M: usize, T> {
+    data: &'a [T],
+    size: [usize; M],
+    stride: [isize; M],
+    offset: usize,
+    step: isize,
+    range: Range<usize>,
+}
+impl<'a, const M: usize, T> AxisIter<'a, M, T> {
+    fn new<const N: usize>(view: NArray<N, &'a [T]>, axis: usize) -> Self {
+        Self {
+            data: view.data,
+            size: remove_axis(&view.size, axis),
+            stride: remove_axis(&view.stride, axis),
+            offset: view.offset,
+            step: view.stride[axis],
+            range: 0..view.size[axis],
+        }
+    }
+    fn get(&self, index: usize) -> NArray<M, &'a [T]> {
+        NArray {
+            size: self.size,
+            stride: self.stride,
+            offset: self.offset.wrapping_add_signed(index as isize * self.step),
+            data: self.data,
+        }
+    }
+}
+impl<'a, const M: usize, T> Iterator for AxisIter<'a, M, T> {
+    type Item = NArray<M, &'a [T]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(self.get(index))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+impl<const M: usize, T> DoubleEndedIterator for AxisIter<'_, M, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(self.get(index))
+    }
+}
+impl<const M: usize, T> ExactSizeIterator for AxisIter<'_, M, T> {}
+
+pub struct AxisIterMut<'a, const M: usize, T> {
+    data: RawMut<'a, T>,
+    size: [usize; M],
+    stride: [isize; M],
+    offset: usize,
+    step: isize,
+    range: Range<usize>,
+}
+impl<'a, const M: usize, T> AxisIterMut<'a, M, T> {
+    fn new<const N: usize>(view: NArray<N, &'a mut [T]>, axis: usize) -> Self {
+        let view = view.into_raw();
+        Self {
+            size: remove_axis(&view.size, axis),
+            stride: remove_axis(&view.stride, axis),
+            offset: view.offset,
+            step: view.stride[axis],
+            range: 0..view.size[axis],
+            data: view.data,
+        }
+    }
+    fn get(&mut self, index: usize) -> NArray<M, RawMut<'a, T>> {
+        NArray {
+            size: self.size,
+            stride: self.stride,
+            offset: self.offset.wrapping_add_signed(index as isize * self.step),
+            // SAFETY: slices at different indices along the axis of a disjoint view never share an element
+            data: unsafe { self.data.alias() },
+        }
+    }
+}
+impl<'a, const M: usize, T> Iterator for AxisIterMut<'a, M, T> {
+    type Item = NArray<M, RawMut<'a, T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(self.get(index))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+impl<const M: usize, T> DoubleEndedIterator for AxisIterMut<'_, M, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(self.get(index))
+    }
+}
+impl<const M: usize, T> ExactSizeIterator for AxisIterMut<'_, M, T> {}
+
+impl<const N: usize, D: Storage> NArray<N, D> {
+    /// One-dimensional view of all elements along `axis` that share the other coordinates with `pos`.
+    pub fn lane(&self, axis: usize, mut pos: [usize; N]) -> NArray<1, &[D::Elem]> {
+        pos[axis] = 0;
+        assert!(
+            (0..N).all(|i| i == axis || pos[i] < self.size[i]),
+            "position out of bounds"
+        );
+        NArray {
+            size: [self.size[axis]],
+            stride: [self.stride[axis]],
+            offset: address(self.offset, pos, &self.stride),
+            data: self.data.as_slice(),
+        }
+    }
+    pub fn lanes(&self, axis: usize) -> impl Iterator<Item = NArray<1, &[D::Elem]>> {
+        self.enumerate_lanes(axis).map(|(_, lane)| lane)
+    }
+    /// Like `lanes`, but also yields the position of the first element of each lane.
+    pub fn enumerate_lanes(&self, axis: usize) -> Lanes<'_, N, D::Elem> {
+        Lanes::new(self.as_view(), axis)
+    }
+    /// Slices orthogonal to `axis` in order, `M` has to be `N - 1`.
+    pub fn axis_iter<const M: usize>(&self, axis: usize) -> AxisIter<'_, M, D::Elem> {
+        AxisIter::new(self.as_view(), axis)
+    }
+    pub fn enumerate_axis<const M: usize>(
+        &self,
+        axis: usize,
+    ) -> Enumerate<AxisIter<'_, M, D::Elem>> {
+        self.axis_iter(axis).enumerate()
+    }
+}
+impl<const N: usize, D: StorageMut> NArray<N, D> {
+    /// # Panics
+    /// If two positions share an element, e.g. because of a zero stride.
+    pub fn lanes_mut(
+        &mut self,
+        axis: usize,
+    ) -> impl Iterator<Item = NArray<1, RawMut<'_, D::Elem>>> {
+        self.enumerate_lanes_mut(axis).map(|(_, lane)| lane)
+    }
+    pub fn enumerate_lanes_mut(&mut self, axis: usize) -> LanesMut<'_, N, D::Elem> {
+        LanesMut::new(self.as_view_mut(), axis)
+    }
+    /// # Panics
+    /// If two positions share an element, e.g. because of a zero stride.
+    pub fn axis_iter_mut<const M: usize>(&mut self, axis: usize) -> AxisIterMut<'_, M, D::Elem> {
+        AxisIterMut::new(self.as_view_mut(), axis)
+    }
+    pub fn enumerate_axis_mut<const M: usize>(
+        &mut self,
+        axis: usize,
+    ) -> Enumerate<AxisIterMut<'_, M, D::Elem>> {
+        self.axis_iter_mut(axis).enumerate()
+    }
+}
+impl<D: Storage> NArray<2, D> {
+    /// Rows from top to bottom, each indexed by `x`.
+    pub fn rows(&self) -> AxisIter<'_, 1, D::Elem> {
+        self.axis_iter(1)
+    }
+    /// Columns from left to right, each indexed by `y`.
+    pub fn columns(&self) -> AxisIter<'_, 1, D::Elem> {
+        self.axis_iter(0)
+    }
+}
+impl<D: StorageMut> NArray<2, D> {
+    pub fn rows_mut(&mut self) -> AxisIterMut<'_, 1, D::Elem> {
+        self.axis_iter_mut(1)
+    }
+    pub fn columns_mut(&mut self) -> AxisIterMut<'_, 1, D::Elem> {
+        self.axis_iter_mut(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::narray::test::grid;
+
+    #[test]
+    fn lanes() {
+        let a = grid::<i32>([3, 2]);
+        let rows: Vec<Vec<i32>> = a
+            .rows()
+            .map(|row| row.iter().map(|(_, x)| *x).collect())
+            .collect();
+        assert_eq!(rows, [[0, 1, 2], [10, 11, 12]]);
+        let columns: Vec<i32> = a.columns().map(|column| column.sum()).collect();
+        assert_eq!(columns, [10, 12, 14]);
+        let lanes: Vec<_> = a
+            .enumerate_lanes(1)
+            .map(|(pos, lane)| (pos, lane[[1]]))
+            .collect();
+        assert_eq!(lanes, [([0, 0], 10), ([1, 0], 11), ([2, 0], 12)]);
+        assert_eq!(
+            a.lane(0, [2, 1])
+                .iter()
+                .map(|(_, x)| *x)
+                .collect::<Vec<_>>(),
+            [10, 11, 12]
+        );
+        let empty = NArray::<2, Box<[i32]>>::new([0, 3]);
+        assert_eq!(empty.lanes(0).count(), 3);
+        assert_eq!(empty.rows().count(), 3);
+    }
+
+    #[test]
+    fn lanes_mut() {
+        let mut a = grid::<i32>([3, 2]);
+        // all lanes are alive at the same time
+        let mut columns: Vec<_> = a.lanes_mut(1).collect();
+        for (x, column) in columns.iter_mut().enumerate() {
+            column[[0]] += 100 * x as i32;
+        }
+        for column in columns {
+            for (_, value) in column {
+                *value += 1;
+            }
+        }
+        assert_eq!(
+            a.rows()
+                .next()
+                .unwrap()
+                .iter()
+                .map(|(_, x)| *x)
+                .collect::<Vec<_>>(),
+            [1, 102, 203]
+        );
+        for (y, mut row) in a.enumerate_axis_mut::<1>(1).rev() {
+            row[[0]] = y as i32;
+        }
+        assert_eq!((a[[0, 0]], a[[0, 1]], a[[1, 1]]), (0, 1, 12));
+        for (_, x) in a.iter_mut() {
+            *x *= 2;
+        }
+        assert_eq!(a[[2, 0]], 406);
+    }
+
+    #[test]
+    #[should_panic = "alias"]
+    fn aliasing_iter_mut() {
+        let mut data = [0u8; 4];
+        let mut a = NArray::from_buffer_with_stride(&mut data[..], [2, 3], [1, 1]);
+        a.iter_mut().count();
+    }
+
+    #[test]
+    #[should_panic = "alias"]
+    fn aliasing_lanes_mut() {
+        let mut data = [0u8; 4];
+        let mut a = NArray::from_buffer_with_stride(&mut data[..], [2, 3], [0, 1]);
+        a.lanes_mut(0).count();
+    }
+}
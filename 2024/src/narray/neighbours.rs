@@ -0,0 +1,246 @@
+use super::{Element, NArray, Storage, address};
+
+/// Shape of the neighbourhood around a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// Positions differing by one along a single axis, 4 in 2D and `2N` in general.
+    VonNeumann,
+    /// Positions differing by at most one along every axis, 8 in 2D and `3^N - 1` in general.
+    Moore,
+}
+impl Neighbourhood {
+    pub const fn count<const N: usize>(self) -> usize {
+        match self {
+            Self::VonNeumann => 2 * N,
+            Self::Moore => 3usize.pow(N as u32) - 1,
+        }
+    }
+    /// The `index`-th offset, never the origin itself.
+    pub const fn offset<const N: usize>(self, index: usize) -> [isize; N] {
+        let mut result = [0; N];
+        match self {
+            Self::VonNeumann => result[index / 2] = [-1, 1][index % 2],
+            Self::Moore => {
+                // skip the center, which has all digits equal to one in base 3
+                let mut index = if index < self.count::<N>() / 2 {
+                    index
+                } else {
+                    index + 1
+                };
+                let mut i = 0;
+                while i < N {
+                    result[i] = (index % 3) as isize - 1;
+                    index /= 3;
+                    i += 1;
+                }
+            }
+        }
+        result
+    }
+    pub fn offsets<const N: usize>(self) -> impl Iterator<Item = [isize; N]> {
+        (0..self.count::<N>()).map(move |index| self.offset(index))
+    }
+}
+
+impl<const N: usize, D> NArray<N, D> {
+    pub fn in_bounds(&self, pos: [usize; N]) -> bool {
+        pos.iter().zip(&self.size).all(|(i, n)| i < n)
+    }
+    /// `pos + offset`, if that is still inside the array.
+    pub fn checked_shift(&self, pos: [usize; N], offset: [isize; N]) -> Option<[usize; N]> {
+        let mut result = [0; N];
+        for i in 0..N {
+            result[i] = pos[i]
+                .checked_add_signed(offset[i])
+                .filter(|x| *x < self.size[i])?;
+        }
+        Some(result)
+    }
+    /// `pos + offset`, wrapping around at the borders like on a torus.
+    pub fn wrapping_shift(&self, pos: [usize; N], offset: [isize; N]) -> [usize; N] {
+        let mut result = [0; N];
+        for i in 0..N {
+            let size = self.size[i] as isize;
+            result[i] = (pos[i] as isize + offset[i]).rem_euclid(size) as usize;
+        }
+        result
+    }
+}
+
+/// Iterator over the neighbours of a position, yielding `None` for neighbours outside of the array.
+pub struct Neighbours<'a, const N: usize, T> {
+    view: NArray<N, &'a [T]>,
+    pos: [usize; N],
+    kind: Neighbourhood,
+    wrapping: bool,
+    index: usize,
+}
+impl<'a, const N: usize, T> Iterator for Neighbours<'a, N, T> {
+    type Item = ([isize; N], Option<([usize; N], &'a T)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.kind.count::<N>() {
+            return None;
+        }
+        let offset = self.kind.offset(self.index);
+        self.index += 1;
+        let pos = if self.wrapping {
+            Some(self.view.wrapping_shift(self.pos, offset))
+        } else {
+            self.view.checked_shift(self.pos, offset)
+        };
+        let data = self.view.data;
+        let neighbour = pos.map(|pos| {
+            (
+                pos,
+                &data[address(self.view.offset, pos, &self.view.stride)],
+            )
+        });
+        Some((offset, neighbour))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.kind.count::<N>() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+impl<const N: usize, T> ExactSizeIterator for Neighbours<'_, N, T> {}
+
+/// Iterator walking in a straight line from a position (inclusive) until it leaves the array.
+pub struct Ray<'a, const N: usize, T> {
+    view: NArray<N, &'a [T]>,
+    pos: Option<[usize; N]>,
+    direction: [isize; N],
+}
+impl<'a, const N: usize, T> Iterator for Ray<'a, N, T> {
+    type Item = ([usize; N], &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.pos?;
+        self.pos = self.view.checked_shift(pos, self.direction);
+        let data = self.view.data;
+        Some((
+            pos,
+            &data[address(self.view.offset, pos, &self.view.stride)],
+        ))
+    }
+}
+
+impl<const N: usize, D: Storage> NArray<N, D> {
+    /// Neighbours inside the array as `(offset, pos, value)`.
+    pub fn neighbours(
+        &self,
+        pos: [usize; N],
+        kind: Neighbourhood,
+    ) -> impl Iterator<Item = ([isize; N], [usize; N], &Element<D>)> {
+        self.padded_neighbours(pos, kind)
+            .filter_map(|(offset, neighbour)| neighbour.map(|(pos, value)| (offset, pos, value)))
+    }
+    /// Neighbours as `(offset, pos, value)`, wrapping around at the borders.
+    pub fn wrapping_neighbours(
+        &self,
+        pos: [usize; N],
+        kind: Neighbourhood,
+    ) -> impl Iterator<Item = ([isize; N], [usize; N], &Element<D>)> {
+        Neighbours {
+            view: self.as_view(),
+            pos,
+            kind,
+            wrapping: true,
+            index: 0,
+        }
+        .map(|(offset, neighbour)| {
+            // SAFETY: unwrap: wrapping never leaves the array
+            let (pos, value) = neighbour.unwrap();
+            (offset, pos, value)
+        })
+    }
+    /// All neighbours in a fixed order, `None` for the ones outside of the array.
+    pub fn padded_neighbours(
+        &self,
+        pos: [usize; N],
+        kind: Neighbourhood,
+    ) -> Neighbours<'_, N, D::Elem> {
+        Neighbours {
+            view: self.as_view(),
+            pos,
+            kind,
+            wrapping: false,
+            index: 0,
+        }
+    }
+    /// Positions and values from `pos` along `direction` until the border.
+    pub fn ray(&self, pos: [usize; N], direction: [isize; N]) -> Ray<'_, N, D::Elem> {
+        Ray {
+            view: self.as_view(),
+            pos: self.in_bounds(pos).then_some(pos),
+            direction,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn moore_skips_the_centre() {
+        let offsets: Vec<[isize; 3]> = Neighbourhood::Moore.offsets().collect();
+        assert_eq!(offsets.len(), 26);
+        assert!(!offsets.contains(&[0, 0, 0]));
+        // the offsets next to the skipped centre
+        assert_eq!(Neighbourhood::Moore.offset::<3>(12), [-1, 0, 0]);
+        assert_eq!(Neighbourhood::Moore.offset::<3>(13), [1, 0, 0]);
+        let mut sorted = offsets.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 26);
+        assert!(offsets.iter().all(|o| o.iter().all(|d| d.abs() <= 1)));
+        assert_eq!(
+            Neighbourhood::Moore.offsets::<1>().collect::<Vec<_>>(),
+            [[-1], [1]]
+        );
+    }
+
+    #[test]
+    fn von_neumann() {
+        let offsets: Vec<[isize; 3]> = Neighbourhood::VonNeumann.offsets().collect();
+        assert_eq!(
+            offsets,
+            [
+                [-1, 0, 0],
+                [1, 0, 0],
+                [0, -1, 0],
+                [0, 1, 0],
+                [0, 0, -1],
+                [0, 0, 1]
+            ]
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let a = NArray::<2, Box<[i32]>>::from_fn([3, 3], |[x, y]| (x + 10 * y) as i32);
+        let n: Vec<_> = a
+            .neighbours([0, 0], Neighbourhood::Moore)
+            .map(|(_, pos, v)| (pos, *v))
+            .collect();
+        assert_eq!(n, [([1, 0], 1), ([0, 1], 10), ([1, 1], 11)]);
+        assert_eq!(a.padded_neighbours([0, 0], Neighbourhood::Moore).count(), 8);
+        assert_eq!(a.neighbours([1, 1], Neighbourhood::VonNeumann).count(), 4);
+        let w: Vec<_> = a
+            .wrapping_neighbours([0, 0], Neighbourhood::VonNeumann)
+            .map(|(_, pos, _)| pos)
+            .collect();
+        assert_eq!(w, [[2, 0], [1, 0], [0, 2], [0, 1]]);
+    }
+
+    #[test]
+    fn rays() {
+        let a = NArray::<2, Box<[i32]>>::from_fn([3, 3], |[x, y]| (x + 10 * y) as i32);
+        let r: Vec<_> = a.ray([0, 0], [1, 1]).map(|(_, v)| *v).collect();
+        assert_eq!(r, [0, 11, 22]);
+        let r: Vec<_> = a.ray([2, 1], [-1, 0]).map(|(pos, _)| pos).collect();
+        assert_eq!(r, [[2, 1], [1, 1], [0, 1]]);
+        assert_eq!(a.ray([3, 0], [1, 1]).count(), 0);
+    }
+}
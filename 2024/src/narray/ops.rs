@@ -0,0 +1,240 @@
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Neg, Not, Rem, RemAssign, Sub, SubAssign,
+};
+
+use num_traits::Zero;
+
+use super::{Element, NArray, Storage, StorageMut, lanes::remove_axis};
+
+/// Types that are broadcast to every element when used as an operand next to an `NArray`.
+pub trait Scalar: Copy {}
+macro_rules! impl_scalar {
+    ($($type: ty),+) => {
+        $(
+            impl Scalar for $type {}
+        )+
+    };
+}
+impl_scalar!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char
+);
+
+/// Inserts `index` at `axis`, the inverse of dropping that axis from a position.
+fn insert_axis<const N: usize, const M: usize>(
+    pos: [usize; M],
+    axis: usize,
+    index: usize,
+) -> [usize; N] {
+    let mut result = [0; N];
+    result[..axis].copy_from_slice(&pos[..axis]);
+    result[axis] = index;
+    result[axis + 1..].copy_from_slice(&pos[axis..]);
+    result
+}
+
+impl<const N: usize, D: Storage> NArray<N, D> {
+    pub fn map<U>(&self, mut f: impl FnMut(&Element<D>) -> U) -> NArray<N, Box<[U]>> {
+        NArray::from_fn(self.size, |pos| f(&self[pos]))
+    }
+    /// # Panics
+    /// If the sizes of both arrays differ.
+    pub fn zip_map<E: Storage, U>(
+        &self,
+        other: &NArray<N, E>,
+        mut f: impl FnMut(&Element<D>, &Element<E>) -> U,
+    ) -> NArray<N, Box<[U]>> {
+        assert_eq!(self.size, other.size, "size mismatch");
+        NArray::from_fn(self.size, |pos| f(&self[pos], &other[pos]))
+    }
+    /// Collapses `axis` by calling `f` with every lane along it, `M` has to be `N - 1`.
+    pub fn map_axis<const M: usize, B>(
+        &self,
+        axis: usize,
+        mut f: impl FnMut(NArray<1, &[D::Elem]>) -> B,
+    ) -> NArray<M, Box<[B]>> {
+        NArray::from_fn(remove_axis(&self.size, axis), |pos| {
+            f(self.lane(axis, insert_axis(pos, axis, 0)))
+        })
+    }
+    pub fn any(&self, mut f: impl FnMut(&Element<D>) -> bool) -> bool {
+        self.iter().any(|(_, x)| f(x))
+    }
+    pub fn all(&self, mut f: impl FnMut(&Element<D>) -> bool) -> bool {
+        self.iter().all(|(_, x)| f(x))
+    }
+    pub fn any_axis<const M: usize>(
+        &self,
+        axis: usize,
+        mut f: impl FnMut(&Element<D>) -> bool,
+    ) -> NArray<M, Box<[bool]>> {
+        self.map_axis(axis, |lane| lane.any(&mut f))
+    }
+    pub fn all_axis<const M: usize>(
+        &self,
+        axis: usize,
+        mut f: impl FnMut(&Element<D>) -> bool,
+    ) -> NArray<M, Box<[bool]>> {
+        self.map_axis(axis, |lane| lane.all(&mut f))
+    }
+}
+impl<const N: usize, T: Zero + Clone, D: Storage<Elem = T>> NArray<N, D> {
+    pub fn sum(&self) -> T {
+        self.iter().fold(T::zero(), |acc, (_, x)| acc + x.clone())
+    }
+    pub fn sum_axis<const M: usize>(&self, axis: usize) -> NArray<M, Box<[T]>> {
+        self.map_axis(axis, |lane| lane.sum())
+    }
+}
+impl<const N: usize, T: Ord + Clone, D: Storage<Elem = T>> NArray<N, D> {
+    pub fn min(&self) -> Option<&T> {
+        self.iter().map(|(_, x)| x).min()
+    }
+    pub fn max(&self) -> Option<&T> {
+        self.iter().map(|(_, x)| x).max()
+    }
+    /// `None` if `axis` has length zero.
+    pub fn min_axis<const M: usize>(&self, axis: usize) -> Option<NArray<M, Box<[T]>>> {
+        (self.size[axis] != 0).then(|| self.map_axis(axis, |lane| lane.min().unwrap().clone()))
+    }
+    /// `None` if `axis` has length zero.
+    pub fn max_axis<const M: usize>(&self, axis: usize) -> Option<NArray<M, Box<[T]>>> {
+        (self.size[axis] != 0).then(|| self.map_axis(axis, |lane| lane.max().unwrap().clone()))
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($op: ident, $fn: ident, $op_assign: ident, $fn_assign: ident) => {
+        impl<const N: usize, T, U, D, E> $op<&NArray<N, E>> for &NArray<N, D>
+        where
+            T: Clone + $op<U>,
+            U: Clone,
+            D: Storage<Elem = T>,
+            E: Storage<Elem = U>,
+        {
+            type Output = NArray<N, Box<[T::Output]>>;
+
+            fn $fn(self, other: &NArray<N, E>) -> Self::Output {
+                self.zip_map(other, |a, b| a.clone().$fn(b.clone()))
+            }
+        }
+        impl<const N: usize, T, S, D> $op<S> for &NArray<N, D>
+        where
+            T: Clone + $op<S>,
+            S: Scalar,
+            D: Storage<Elem = T>,
+        {
+            type Output = NArray<N, Box<[T::Output]>>;
+
+            fn $fn(self, other: S) -> Self::Output {
+                self.map(|a| a.clone().$fn(other))
+            }
+        }
+        impl<const N: usize, T, U, D, E> $op_assign<&NArray<N, E>> for NArray<N, D>
+        where
+            T: $op_assign<U>,
+            U: Clone,
+            D: StorageMut<Elem = T>,
+            E: Storage<Elem = U>,
+        {
+            fn $fn_assign(&mut self, other: &NArray<N, E>) {
+                assert_eq!(self.size, other.size, "size mismatch");
+                for (pos, a) in &mut *self {
+                    a.$fn_assign(other[pos].clone());
+                }
+            }
+        }
+        impl<const N: usize, T, S, D> $op_assign<S> for NArray<N, D>
+        where
+            T: $op_assign<S>,
+            S: Scalar,
+            D: StorageMut<Elem = T>,
+        {
+            fn $fn_assign(&mut self, other: S) {
+                for (_, a) in &mut *self {
+                    a.$fn_assign(other);
+                }
+            }
+        }
+    };
+}
+impl_binary_op!(Add, add, AddAssign, add_assign);
+impl_binary_op!(Sub, sub, SubAssign, sub_assign);
+impl_binary_op!(Mul, mul, MulAssign, mul_assign);
+impl_binary_op!(Div, div, DivAssign, div_assign);
+impl_binary_op!(Rem, rem, RemAssign, rem_assign);
+impl_binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_binary_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+macro_rules! impl_unary_op {
+    ($op: ident, $fn: ident) => {
+        impl<const N: usize, T, D> $op for &NArray<N, D>
+        where
+            T: Clone + $op,
+            D: Storage<Elem = T>,
+        {
+            type Output = NArray<N, Box<[T::Output]>>;
+
+            fn $fn(self) -> Self::Output {
+                self.map(|a| a.clone().$fn())
+            }
+        }
+    };
+}
+impl_unary_op!(Neg, neg);
+impl_unary_op!(Not, not);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{RangeAny, narray::test::grid, view};
+
+    #[test]
+    fn element_wise() {
+        let a = grid::<i32>([3, 2]);
+        let b = NArray::<2, Box<[i32]>>::from_fn([3, 2], |[x, _]| x as i32);
+        assert_eq!((&a + &b)[[2, 1]], 14);
+        assert_eq!((&a * 2)[[1, 1]], 22);
+        assert_eq!((-&a)[[1, 0]], -1);
+        let mut c = a.clone();
+        c -= &b;
+        c += 1;
+        assert_eq!(c, NArray::from_fn([3, 2], |[_, y]| 1 + 10 * y as i32));
+        // views keep their own size and offset
+        let v: NArray<2, &[i32]> = view![a, 1.., ..].unwrap();
+        let d = &v + &v;
+        assert_eq!(d.size(), &[2, 2]);
+        assert_eq!((d[[0, 0]], d[[1, 1]]), (2, 24));
+        let bits = &NArray::<1, Box<[u8]>>::from_fn([3], |[x]| x as u8) & 1u8;
+        assert_eq!(bits, NArray::from_iter([3], [0, 1, 0]).unwrap());
+    }
+
+    #[test]
+    #[should_panic = "size mismatch"]
+    fn size_mismatch() {
+        let mut a = grid::<i32>([3, 2]);
+        a += &a.transpose().map(|x| *x);
+    }
+
+    #[test]
+    fn reductions() {
+        let a = grid::<i32>([3, 2]);
+        assert_eq!(a.sum(), 1 + 2 + 10 + 11 + 12);
+        assert_eq!((a.min(), a.max()), (Some(&0), Some(&12)));
+        let s: NArray<1, Box<[i32]>> = a.sum_axis(0);
+        assert_eq!((s[[0]], s[[1]]), (3, 33));
+        let s: NArray<1, Box<[i32]>> = a.sum_axis(1);
+        assert_eq!((s[[0]], s[[1]], s[[2]]), (10, 12, 14));
+        let m: NArray<1, Box<[i32]>> = a.max_axis(1).unwrap();
+        assert_eq!((m[[0]], m[[2]]), (10, 12));
+        let any: NArray<1, Box<[bool]>> = a.any_axis(0, |x| *x > 11);
+        assert_eq!((any[[0]], any[[1]]), (false, true));
+        assert!(a.all(|x| *x >= 0) && !a.any(|x| *x > 12));
+        let empty = NArray::<2, Box<[i32]>>::new([0, 2]);
+        assert_eq!((empty.sum(), empty.min()), (0, None));
+        assert!(empty.max_axis::<1>(0).is_none());
+        let scalar = NArray::<0, Box<[i32]>>::filled([], 7);
+        assert_eq!(scalar.sum(), 7);
+    }
+}
@@ -0,0 +1,294 @@
+use std::ops::Range;
+
+use rayon::iter::{
+    IndexedParallelIterator, ParallelIterator,
+    plumbing::{Consumer, Producer, ProducerCallback, UnindexedConsumer, bridge},
+};
+
+use super::{Lanes, NArray, RawMut, Storage, StorageMut, address, next, prod};
+
+/// Positions of an array of size `size` in memory order.
+fn positions<const N: usize>(size: [usize; N]) -> impl Iterator<Item = [usize; N]> {
+    let mut pos = [0; N];
+    (0..prod(&size)).map(move |_| {
+        let current = pos;
+        next(&mut pos, &size);
+        current
+    })
+}
+
+/// Indexed parallel iterator driving a producer that is also its own sequential iterator.
+struct Parallel<P>(P);
+impl<P: Producer<IntoIter = P, Item: Send> + ExactSizeIterator> ParallelIterator for Parallel<P> {
+    type Item = <P as Producer>::Item;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+impl<P: Producer<IntoIter = P, Item: Send> + ExactSizeIterator> IndexedParallelIterator
+    for Parallel<P>
+{
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        callback.callback(self.0)
+    }
+}
+
+/// Slices of a view at each index along `axis` as `(index, slice)`, keeping `axis` with length one.
+///
+/// Without an axis the whole view is the only slice.
+struct Slices<'a, const N: usize, T> {
+    view: NArray<N, &'a [T]>,
+    axis: Option<usize>,
+    range: Range<usize>,
+}
+impl<'a, const N: usize, T> Slices<'a, N, T> {
+    fn new(view: NArray<N, &'a [T]>, axis: Option<usize>) -> Self {
+        let len = axis.map_or(1, |axis| view.size[axis]);
+        Self {
+            view,
+            axis,
+            range: 0..len,
+        }
+    }
+    fn get(&self, index: usize) -> (usize, NArray<N, &'a [T]>) {
+        let mut slice = self.view.clone();
+        if let Some(axis) = self.axis {
+            slice.size[axis] = 1;
+            slice.offset = address(slice.offset, [index], &[slice.stride[axis]]);
+        }
+        (index, slice)
+    }
+}
+impl<'a, const N: usize, T> Iterator for Slices<'a, N, T> {
+    type Item = (usize, NArray<N, &'a [T]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(self.get(index))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+impl<const N: usize, T> DoubleEndedIterator for Slices<'_, N, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(self.get(index))
+    }
+}
+impl<const N: usize, T> ExactSizeIterator for Slices<'_, N, T> {}
+impl<'a, const N: usize, T: Sync> Producer for Slices<'a, N, T> {
+    type Item = (usize, NArray<N, &'a [T]>);
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let mid = self.range.start + index;
+        let left = Self {
+            view: self.view.clone(),
+            axis: self.axis,
+            range: self.range.start..mid,
+        };
+        let right = Self {
+            range: mid..self.range.end,
+            ..self
+        };
+        (left, right)
+    }
+}
+
+struct SlicesMut<'a, const N: usize, T> {
+    view: NArray<N, RawMut<'a, T>>,
+    axis: Option<usize>,
+    range: Range<usize>,
+}
+impl<'a, const N: usize, T> SlicesMut<'a, N, T> {
+    /// # Panics
+    /// If two positions of `view` share an element.
+    fn new(view: NArray<N, &'a mut [T]>, axis: Option<usize>) -> Self {
+        let view = view.into_raw();
+        let len = axis.map_or(1, |axis| view.size[axis]);
+        Self {
+            view,
+            axis,
+            range: 0..len,
+        }
+    }
+    fn get(&mut self, index: usize) -> (usize, NArray<N, RawMut<'a, T>>) {
+        let mut slice = NArray {
+            size: self.view.size,
+            stride: self.view.stride,
+            offset: self.view.offset,
+            // SAFETY: slices at different indices along the axis never share an element
+            data: unsafe { self.view.data.alias() },
+        };
+        if let Some(axis) = self.axis {
+            slice.size[axis] = 1;
+            slice.offset = address(slice.offset, [index], &[slice.stride[axis]]);
+        }
+        (index, slice)
+    }
+}
+impl<'a, const N: usize, T> Iterator for SlicesMut<'a, N, T> {
+    type Item = (usize, NArray<N, RawMut<'a, T>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(self.get(index))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+impl<const N: usize, T> DoubleEndedIterator for SlicesMut<'_, N, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(self.get(index))
+    }
+}
+impl<const N: usize, T> ExactSizeIterator for SlicesMut<'_, N, T> {}
+impl<'a, const N: usize, T: Send> Producer for SlicesMut<'a, N, T> {
+    type Item = (usize, NArray<N, RawMut<'a, T>>);
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let mid = self.range.start + index;
+        let left = Self {
+            view: NArray {
+                size: self.view.size,
+                stride: self.view.stride,
+                offset: self.view.offset,
+                // SAFETY: both halves only hand out slices from their own disjoint ranges
+                data: unsafe { self.view.data.alias() },
+            },
+            axis: self.axis,
+            range: self.range.start..mid,
+        };
+        let right = Self {
+            range: mid..self.range.end,
+            ..self
+        };
+        (left, right)
+    }
+}
+
+impl<const N: usize, T: Sync, D: Storage<Elem = T>> NArray<N, D> {
+    /// Parallel version of `iter`, splitting the work along the outermost axis.
+    pub fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = ([usize; N], &'a T)>
+    where
+        T: 'a,
+    {
+        let axis = N.checked_sub(1);
+        Parallel(Slices::new(self.as_view(), axis)).flat_map_iter(move |(index, slice)| {
+            let data = slice.data;
+            positions(slice.size).map(move |mut pos| {
+                let value = &data[address(slice.offset, pos, &slice.stride)];
+                if let Some(axis) = axis {
+                    pos[axis] = index;
+                }
+                (pos, value)
+            })
+        })
+    }
+    /// Parallel version of `lanes`, splitting the work along the outermost other axis.
+    pub fn par_lanes<'a>(&'a self, axis: usize) -> impl ParallelIterator<Item = NArray<1, &'a [T]>>
+    where
+        T: 'a,
+    {
+        assert!(axis < N, "invalid axis");
+        let outer = (0..N).rev().find(|&i| i != axis);
+        Parallel(Slices::new(self.as_view(), outer))
+            .flat_map_iter(move |(_, slice)| Lanes::new(slice, axis).map(|(_, lane)| lane))
+    }
+}
+impl<const N: usize, T: Send, D: StorageMut<Elem = T>> NArray<N, D> {
+    /// Parallel version of `iter_mut`, splitting the work along the outermost axis.
+    ///
+    /// # Panics
+    /// If two positions share an element, e.g. because of a zero stride.
+    pub fn par_iter_mut<'a>(&'a mut self) -> impl ParallelIterator<Item = ([usize; N], &'a mut T)>
+    where
+        T: 'a,
+    {
+        let axis = N.checked_sub(1);
+        Parallel(SlicesMut::new(self.as_view_mut(), axis)).flat_map_iter(move |(index, slice)| {
+            slice.into_iter().map(move |(mut pos, value)| {
+                if let Some(axis) = axis {
+                    pos[axis] = index;
+                }
+                (pos, value)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{RangeAny, RangeStep, view};
+
+    fn cube() -> NArray<3, Vec<i64>> {
+        NArray::from_fn([5, 4, 7], |[x, y, z]| (x + 10 * y + 100 * z) as i64)
+    }
+
+    #[test]
+    fn matches_sequential() {
+        let a = cube();
+        let seq: Vec<_> = a.iter().map(|(pos, x)| (pos, *x)).collect();
+        let par: Vec<_> = a.par_iter().map(|(pos, x)| (pos, *x)).collect();
+        assert_eq!(seq, par);
+        let t = a.permute([2, 0, 1]);
+        let v: NArray<3, _> = view![t, (..).rev(), (1..5).step_by(2), ..].unwrap();
+        let seq: Vec<_> = v.iter().map(|(pos, x)| (pos, *x)).collect();
+        let par: Vec<_> = v.par_iter().map(|(pos, x)| (pos, *x)).collect();
+        assert_eq!(seq, par);
+        for axis in 0..3 {
+            let seq: Vec<i64> = a.lanes(axis).map(|lane| lane.sum()).collect();
+            let par: Vec<i64> = a.par_lanes(axis).map(|lane| lane.sum()).collect();
+            assert_eq!(seq, par);
+        }
+        let b = NArray::<1, [u8; 3]>::filled([3], 2);
+        assert_eq!(b.par_lanes(0).count(), 1);
+        assert_eq!(b.par_iter().map(|(_, x)| *x as u32).sum::<u32>(), 6);
+    }
+
+    #[test]
+    fn par_iter_mut() {
+        let mut a = cube();
+        a.flip_mut(1)
+            .par_iter_mut()
+            .for_each(|([x, y, z], v)| *v -= (x + 10 * (3 - y) + 100 * z) as i64);
+        assert!(a.all(|x| *x == 0));
+        let mut c = NArray::<0, Vec<u8>>::filled([], 5);
+        c.par_iter_mut().for_each(|(_, x)| *x += 1);
+        assert_eq!(c[[]], 6);
+        let mut big = NArray::<2, Vec<u64>>::new([300, 200]);
+        big.par_iter_mut()
+            .for_each(|([x, y], v)| *v = (x * y) as u64);
+        assert_eq!(big.par_iter().map(|(_, x)| *x).sum::<u64>(), big.sum());
+        assert_eq!(big[[299, 199]], 299 * 199);
+    }
+
+    #[test]
+    #[should_panic = "alias"]
+    fn aliasing() {
+        let mut data = [0u8; 4];
+        let mut d = NArray::from_buffer_with_stride(&mut data[..], [2, 3], [1, 1]);
+        d.par_iter_mut().count();
+    }
+}
@@ -0,0 +1,199 @@
+use super::{Element, NArray, Storage, address, next, prod};
+
+/// Iterator over all overlapping sub-views of a fixed size, yielding the position of each window.
+pub struct Windows<'a, const N: usize, T> {
+    view: NArray<N, &'a [T]>,
+    window: [usize; N],
+    outer: [usize; N],
+    current: [usize; N],
+    remaining: usize,
+}
+impl<'a, const N: usize, T> Iterator for Windows<'a, N, T> {
+    type Item = ([usize; N], NArray<N, &'a [T]>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let pos = self.current;
+        next(&mut self.current, &self.outer);
+        let window = NArray {
+            size: self.window,
+            stride: self.view.stride,
+            offset: address(self.view.offset, pos, &self.view.stride),
+            data: self.view.data,
+        };
+        Some((pos, window))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<const N: usize, T> ExactSizeIterator for Windows<'_, N, T> {}
+
+/// Element of the dihedral group of the square, applied to 2D patterns with `y` pointing down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// Clockwise by 90 degrees.
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrored left to right.
+    FlipX,
+    /// Mirrored top to bottom.
+    FlipY,
+    /// Mirrored along the main diagonal.
+    Transpose,
+    /// Mirrored along the anti-diagonal.
+    AntiTranspose,
+}
+impl Symmetry {
+    pub const ROTATIONS: [Self; 4] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+    ];
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipX,
+        Self::FlipY,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+    /// The image of `array` without copying.
+    pub fn transform<D>(self, array: NArray<2, D>) -> NArray<2, D> {
+        match self {
+            Self::Identity => array,
+            Self::Rotate90 => array.rotated(1, [0, 1]),
+            Self::Rotate180 => array.rotated(2, [0, 1]),
+            Self::Rotate270 => array.rotated(3, [0, 1]),
+            Self::FlipX => array.flipped(0),
+            Self::FlipY => array.flipped(1),
+            Self::Transpose => array.reversed_axes(),
+            Self::AntiTranspose => array.reversed_axes().flipped(0).flipped(1),
+        }
+    }
+    pub fn apply<D: Storage<Elem: Clone>>(
+        self,
+        array: &NArray<2, D>,
+    ) -> NArray<2, Box<[Element<D>]>> {
+        self.transform(array.as_view()).map(Clone::clone)
+    }
+}
+
+/// Set of patterns to search for, `None` cells match anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil<const N: usize, T> {
+    patterns: Vec<NArray<N, Box<[Option<T>]>>>,
+}
+impl<const N: usize, T> Stencil<N, T> {
+    pub fn new(pattern: NArray<N, Box<[Option<T>]>>) -> Self {
+        Self {
+            patterns: vec![pattern],
+        }
+    }
+    pub fn patterns(&self) -> &[NArray<N, Box<[Option<T>]>>] {
+        &self.patterns
+    }
+}
+impl<T: Clone + PartialEq> Stencil<2, T> {
+    /// Adds every distinct image of the patterns under `symmetries`, keeping the patterns themselves.
+    pub fn with_symmetries(mut self, symmetries: &[Symmetry]) -> Self {
+        for pattern in self.patterns.clone() {
+            for symmetry in symmetries {
+                let image = symmetry.apply(&pattern);
+                if !self.patterns.contains(&image) {
+                    self.patterns.push(image);
+                }
+            }
+        }
+        self
+    }
+}
+
+impl<const N: usize, D: Storage> NArray<N, D> {
+    /// Overlapping sub-views of size `window` in memory order.
+    pub fn windows(&self, window: [usize; N]) -> impl Iterator<Item = NArray<N, &[D::Elem]>> {
+        self.enumerate_windows(window).map(|(_, view)| view)
+    }
+    /// Like `windows`, but also yields the position of the first element of each window.
+    pub fn enumerate_windows(&self, window: [usize; N]) -> Windows<'_, N, D::Elem> {
+        let mut outer = [0; N];
+        for i in 0..N {
+            outer[i] = (self.size[i] + 1).saturating_sub(window[i]);
+        }
+        Windows {
+            view: self.as_view(),
+            window,
+            outer,
+            current: [0; N],
+            remaining: prod(&outer),
+        }
+    }
+    /// Every placement of any pattern of `stencil` as `(pos, index of the pattern)`.
+    pub fn match_stencil<'a, T>(
+        &'a self,
+        stencil: &'a Stencil<N, T>,
+    ) -> impl Iterator<Item = ([usize; N], usize)> + 'a
+    where
+        Element<D>: PartialEq<T>,
+    {
+        stencil
+            .patterns
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, pattern)| {
+                self.enumerate_windows(pattern.size)
+                    .filter(|(_, window)| {
+                        pattern
+                            .iter()
+                            .all(|(pos, cell)| cell.as_ref().is_none_or(|c| window[pos] == *c))
+                    })
+                    .map(move |(pos, _)| (pos, index))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::narray::test::grid;
+
+    #[test]
+    fn windows() {
+        let a = grid::<u8>([3, 2]);
+        let windows: Vec<_> = a
+            .enumerate_windows([2, 2])
+            .map(|(pos, w)| (pos, w[[1, 1]]))
+            .collect();
+        assert_eq!(windows, [([0, 0], 11), ([1, 0], 12)]);
+        assert_eq!(a.windows([3, 2]).count(), 1);
+        assert_eq!(a.windows([1, 1]).count(), 6);
+        // larger than the array along any axis
+        assert_eq!(a.windows([4, 1]).count(), 0);
+        assert_eq!(a.windows([1, 3]).count(), 0);
+        assert_eq!(a.windows([10, 10]).count(), 0);
+        assert_eq!(
+            NArray::<2, Box<[u8]>>::new([0, 0]).windows([1, 1]).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn symmetries_keep_the_original() {
+        let pattern = NArray::<2, Box<[Option<u8>]>>::from_iter([2, 1], [Some(11), None]).unwrap();
+        // the original is kept even without the identity
+        let stencil = Stencil::new(pattern.clone()).with_symmetries(&[Symmetry::FlipX]);
+        assert_eq!(stencil.patterns().len(), 2);
+        assert_eq!(stencil.patterns()[0], pattern);
+        // duplicates are only added once
+        let stencil = Stencil::new(pattern).with_symmetries(&Symmetry::ALL);
+        assert_eq!(stencil.patterns().len(), 4);
+        let matches: Vec<_> = grid::<u8>([3, 2]).match_stencil(&stencil).collect();
+        // the clockwise rotation has its 11 on top, which fits nowhere
+        assert_eq!(matches, [([1, 1], 0), ([0, 1], 2), ([1, 0], 3)]);
+    }
+}
@@ -0,0 +1,202 @@
+use std::{borrow::Cow, marker::PhantomData};
+
+/// Contiguous buffer an `NArray` keeps its elements in.
+pub trait Storage {
+    type Elem;
+
+    fn as_slice(&self) -> &[Self::Elem];
+}
+pub trait StorageMut: Storage {
+    fn as_mut_slice(&mut self) -> &mut [Self::Elem];
+}
+/// Storage owning its elements, which allows building arrays from scratch.
+pub trait OwnedStorage: StorageMut + Sized {
+    /// Takes the first `len` elements of `iter`, `None` if there are fewer or they don't fit.
+    fn from_elements(len: usize, iter: impl Iterator<Item = Self::Elem>) -> Option<Self>;
+}
+
+impl<T> Storage for Vec<T> {
+    type Elem = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+impl<T> StorageMut for Vec<T> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+impl<T> OwnedStorage for Vec<T> {
+    fn from_elements(len: usize, iter: impl Iterator<Item = T>) -> Option<Self> {
+        let data: Vec<T> = iter.take(len).collect();
+        (data.len() == len).then_some(data)
+    }
+}
+
+impl<T> Storage for Box<[T]> {
+    type Elem = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+impl<T> StorageMut for Box<[T]> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+impl<T> OwnedStorage for Box<[T]> {
+    fn from_elements(len: usize, iter: impl Iterator<Item = T>) -> Option<Self> {
+        Vec::from_elements(len, iter).map(Vec::into_boxed_slice)
+    }
+}
+
+impl<T, const K: usize> Storage for [T; K] {
+    type Elem = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+impl<T, const K: usize> StorageMut for [T; K] {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+impl<T, const K: usize> OwnedStorage for [T; K] {
+    /// Only succeeds for `len == K`, the elements never leave the stack.
+    fn from_elements(len: usize, mut iter: impl Iterator<Item = T>) -> Option<Self> {
+        if len != K {
+            return None;
+        }
+        let data: [Option<T>; K] = std::array::from_fn(|_| iter.next());
+        data.iter()
+            .all(Option::is_some)
+            .then(|| data.map(Option::unwrap))
+    }
+}
+
+impl<T> Storage for &[T] {
+    type Elem = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+impl<T> Storage for &mut [T] {
+    type Elem = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+impl<T> StorageMut for &mut [T] {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: Clone> Storage for Cow<'_, [T]> {
+    type Elem = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+/// Writing clones borrowed data on first use.
+impl<T: Clone> StorageMut for Cow<'_, [T]> {
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self.to_mut()
+    }
+}
+impl<T: Clone> OwnedStorage for Cow<'_, [T]> {
+    fn from_elements(len: usize, iter: impl Iterator<Item = T>) -> Option<Self> {
+        Vec::from_elements(len, iter).map(Cow::Owned)
+    }
+}
+
+/// Buffer shared by views that never reach the same element, like the lanes of an array.
+///
+/// Unlike `&mut [T]` it does not borrow the whole buffer, so it is not a `Storage`. Elements
+/// are only borrowed one at a time through indexing and iteration.
+#[derive(Debug)]
+pub struct RawMut<'a, T> {
+    ptr: *mut T,
+    marker: PhantomData<&'a mut [T]>,
+}
+// SAFETY: same as for `&mut [T]`
+unsafe impl<T: Send> Send for RawMut<'_, T> {}
+unsafe impl<T: Sync> Sync for RawMut<'_, T> {}
+impl<'a, T> RawMut<'a, T> {
+    pub(super) fn new(data: &'a mut [T]) -> Self {
+        Self {
+            ptr: data.as_mut_ptr(),
+            marker: PhantomData,
+        }
+    }
+    /// # Safety
+    /// The two handles must not be used to reach the same element.
+    pub(super) unsafe fn alias(&self) -> Self {
+        Self {
+            ptr: self.ptr,
+            marker: PhantomData,
+        }
+    }
+    pub(super) fn as_ptr(&self) -> *mut T {
+        self.ptr
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{NArray, narray::test::value};
+
+    #[test]
+    fn owned() {
+        let a = NArray::<2, Vec<i32>>::from_fn([3, 2], value);
+        let b = NArray::<2, [i32; 6]>::from_fn([3, 2], value);
+        let c = NArray::<2, Box<[i32]>>::from_fn([3, 2], value);
+        assert_eq!((a[[2, 1]], b[[2, 1]], c[[2, 1]]), (12, 12, 12));
+        assert_eq!((&a + &b)[[1, 1]], 22);
+        assert_eq!(NArray::<2, Cow<[i32]>>::filled([2, 2], 7).sum(), 28);
+    }
+
+    #[test]
+    fn from_elements() {
+        assert_eq!(
+            NArray::<1, Box<[u8]>>::from_iter([3], [1, 2, 3, 4])
+                .unwrap()
+                .sum(),
+            6
+        );
+        assert!(NArray::<1, Vec<u8>>::from_iter([5], [1, 2]).is_none());
+        assert!(NArray::<1, [u8; 2]>::from_iter([2], [1]).is_none());
+        assert!(NArray::<1, [u8; 2]>::from_iter([1], [1, 2]).is_none());
+    }
+
+    #[test]
+    #[should_panic = "storage does not fit"]
+    fn fixed_size_mismatch() {
+        NArray::<2, [i32; 5]>::new([3, 2]);
+    }
+
+    #[test]
+    fn borrowed() {
+        let mut buffer = [0u8; 6];
+        {
+            let mut a = NArray::from_buffer(&mut buffer[..], [2, 3]);
+            a.fill_with(|[x, y]| (x * y) as u8);
+            a[[0, 0]] = 9;
+        }
+        assert_eq!(buffer, [9, 0, 0, 1, 0, 2]);
+        let b = NArray::from_buffer(&buffer[..], [3, 2]);
+        assert_eq!(b.transpose()[[1, 0]], 1);
+        // writing to a borrowed `Cow` copies the buffer first
+        let mut c = NArray::from_buffer(Cow::Borrowed(&buffer[..]), [6]);
+        c.fill(1);
+        assert_eq!(c.sum(), 6);
+        assert_eq!(buffer[0], 9);
+    }
+}
@@ -0,0 +1,175 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use super::{NArray, Storage};
+
+/// Reasons for a string not being a grid of cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// Row `y` has `found` cells instead of as many as the first row.
+    Ragged {
+        y: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The character at `pos` does not convert to a cell.
+    InvalidCell { pos: [usize; 2], char: char },
+}
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ragged { y, expected, found } => {
+                write!(f, "row {y} has {found} cells instead of {expected}")
+            }
+            Self::InvalidCell { pos: [x, y], char } => {
+                write!(f, "invalid cell {char:?} at ({x}, {y})")
+            }
+        }
+    }
+}
+impl Error for GridError {}
+
+impl<T> NArray<2, Box<[T]>> {
+    /// Parses one row per line with one cell per character, indexed by `[x, y]`.
+    ///
+    /// Accepts `\n` and `\r\n` line endings, trailing empty lines are ignored.
+    pub fn from_grid_str(s: &str, mut f: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        Self::try_from_grid_str(s, |c| Some(f(c)))
+    }
+    /// Like `from_grid_str`, but fails for characters that `f` rejects.
+    pub fn try_from_grid_str(
+        s: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, GridError> {
+        let mut data = Vec::with_capacity(s.len());
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in s.trim_end_matches(['\r', '\n']).lines().enumerate() {
+            let start = data.len();
+            for (x, char) in line.chars().enumerate() {
+                let cell = f(char).ok_or(GridError::InvalidCell { pos: [x, y], char })?;
+                data.push(cell);
+            }
+            let found = data.len() - start;
+            let expected = *width.get_or_insert(found);
+            if found != expected {
+                return Err(GridError::Ragged { y, expected, found });
+            }
+            height += 1;
+        }
+        Ok(Self::from_buffer(
+            data.into_boxed_slice(),
+            [width.unwrap_or(0), height],
+        ))
+    }
+}
+/// Converts every character with `T::try_from`.
+impl<T: TryFrom<char>> FromStr for NArray<2, Box<[T]>> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from_grid_str(s, |c| T::try_from(c).ok())
+    }
+}
+
+/// One line per row, cells are written next to each other with the formatting options applied to each.
+impl<T: fmt::Display, D: Storage<Elem = T>> fmt::Display for NArray<2, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for (_, cell) in &row {
+                cell.fmt(f)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes every cell as a character, for grids of bytes which would otherwise print as numbers.
+pub struct DisplayChars<'a, D>(&'a NArray<2, D>);
+impl<D: Storage<Elem: Copy + Into<char>>> fmt::Display for DisplayChars<'_, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.0.rows() {
+            for (_, &cell) in &row {
+                cell.into().fmt(f)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+impl<D: Storage<Elem: Copy + Into<char>>> NArray<2, D> {
+    /// Inverse of parsing for `u8` grids, `to_string` would print the bytes as numbers.
+    pub fn display_chars(&self) -> DisplayChars<'_, D> {
+        DisplayChars(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Grid<T> = NArray<2, Box<[T]>>;
+
+    const GRID: &str = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\n";
+
+    #[test]
+    fn round_trip() {
+        let chars: Grid<char> = GRID.parse().unwrap();
+        assert_eq!(chars.size(), &[10, 3]);
+        assert_eq!(chars[[4, 1]], 'X');
+        assert_eq!(chars.to_string(), GRID);
+        let bytes: Grid<u8> = GRID.parse().unwrap();
+        assert_eq!(bytes[[4, 1]], b'X');
+        assert_eq!(bytes.display_chars().to_string(), GRID);
+        assert!(bytes.to_string().starts_with("7777778388"));
+    }
+
+    #[test]
+    fn line_endings() {
+        let lf: Grid<char> = "ab.\n.cd\n".parse().unwrap();
+        let crlf: Grid<char> = "ab.\r\n.cd\r\n".parse().unwrap();
+        let unterminated: Grid<char> = "ab.\n.cd".parse().unwrap();
+        let trailing: Grid<u8> = "ab.\r\n.cd\r\n\r\n".parse().unwrap();
+        assert_eq!(lf, crlf);
+        assert_eq!(lf, unterminated);
+        assert_eq!(lf.size(), &[3, 2]);
+        assert_eq!(trailing.display_chars().to_string(), "ab.\n.cd\n");
+        assert_eq!("".parse::<Grid<char>>().unwrap().size(), &[0, 0]);
+    }
+
+    #[test]
+    fn errors() {
+        let ragged = GridError::Ragged {
+            y: 1,
+            expected: 2,
+            found: 3,
+        };
+        assert_eq!("ab\nabc\n".parse::<Grid<char>>(), Err(ragged.clone()));
+        assert_eq!(ragged.to_string(), "row 1 has 3 cells instead of 2");
+        // a short last row is not mistaken for a missing line ending
+        assert_eq!(
+            "abc\r\nab".parse::<Grid<char>>(),
+            Err(GridError::Ragged {
+                y: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "ab\na€\n".parse::<Grid<u8>>(),
+            Err(GridError::InvalidCell {
+                pos: [1, 1],
+                char: '€'
+            })
+        );
+        assert!(Grid::try_from_grid_str("12\n3x", |c| c.to_digit(10)).is_err());
+    }
+
+    #[test]
+    fn formatting() {
+        let digits = Grid::from_grid_str("12\n34", |c| c.to_digit(10).unwrap()).unwrap();
+        assert_eq!(digits.sum(), 10);
+        assert_eq!(format!("{digits:>2}"), " 1 2\n 3 4\n");
+        assert_eq!(digits.transpose().to_string(), "13\n24\n");
+    }
+}
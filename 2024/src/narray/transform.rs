@@ -0,0 +1,160 @@
+use super::{NArray, Storage, StorageMut};
+
+impl<const N: usize, D> NArray<N, D> {
+    /// Reorders the axes without copying, axis `i` of the result is axis `axes[i]` of `self`.
+    pub fn permuted_axes(self, axes: [usize; N]) -> Self {
+        let mut seen = [false; N];
+        for &axis in &axes {
+            assert!(axis < N && !seen[axis], "axes are not a permutation");
+            seen[axis] = true;
+        }
+        Self {
+            size: axes.map(|axis| self.size[axis]),
+            stride: axes.map(|axis| self.stride[axis]),
+            ..self
+        }
+    }
+    /// Reverses the order of all axes, for two dimensions this is the transpose.
+    pub fn reversed_axes(mut self) -> Self {
+        self.size.reverse();
+        self.stride.reverse();
+        self
+    }
+    pub fn swapped_axes(mut self, a: usize, b: usize) -> Self {
+        self.size.swap(a, b);
+        self.stride.swap(a, b);
+        self
+    }
+    /// Reverses the order of the elements along `axis`.
+    pub fn flipped(mut self, axis: usize) -> Self {
+        if let Some(last) = self.size[axis].checked_sub(1) {
+            self.offset = self
+                .offset
+                .wrapping_add_signed(last as isize * self.stride[axis]);
+        }
+        self.stride[axis] = -self.stride[axis];
+        self
+    }
+    /// Rotates by `k` quarter turns in the plane of `axes`, turning axis `axes[0]` towards `axes[1]`.
+    ///
+    /// For `[0, 1]` on a `[x, y]` grid with `y` pointing down this is clockwise.
+    pub fn rotated(self, k: isize, [a, b]: [usize; 2]) -> Self {
+        assert!(a != b, "rotation needs two different axes");
+        match k.rem_euclid(4) {
+            0 => self,
+            1 => self.swapped_axes(a, b).flipped(a),
+            2 => self.flipped(a).flipped(b),
+            _ => self.swapped_axes(a, b).flipped(b),
+        }
+    }
+    /// One-dimensional array of the elements with all coordinates equal.
+    pub fn into_diagonal(self) -> NArray<1, D> {
+        NArray {
+            size: [self.size.iter().copied().min().unwrap_or(1)],
+            stride: [self.stride.iter().sum()],
+            offset: self.offset,
+            data: self.data,
+        }
+    }
+}
+impl<const N: usize, D: Storage> NArray<N, D> {
+    pub fn transpose(&self) -> NArray<N, &[D::Elem]> {
+        self.as_view().reversed_axes()
+    }
+    pub fn permute(&self, axes: [usize; N]) -> NArray<N, &[D::Elem]> {
+        self.as_view().permuted_axes(axes)
+    }
+    pub fn flip(&self, axis: usize) -> NArray<N, &[D::Elem]> {
+        self.as_view().flipped(axis)
+    }
+    pub fn rot90(&self, k: isize, axes: [usize; 2]) -> NArray<N, &[D::Elem]> {
+        self.as_view().rotated(k, axes)
+    }
+    /// Elements at `[i, i, ...]`, use `flip(axis).into_diagonal()` for the other diagonals.
+    pub fn diagonal(&self) -> NArray<1, &[D::Elem]> {
+        self.as_view().into_diagonal()
+    }
+}
+impl<const N: usize, D: StorageMut> NArray<N, D> {
+    pub fn transpose_mut(&mut self) -> NArray<N, &mut [D::Elem]> {
+        self.as_view_mut().reversed_axes()
+    }
+    pub fn permute_mut(&mut self, axes: [usize; N]) -> NArray<N, &mut [D::Elem]> {
+        self.as_view_mut().permuted_axes(axes)
+    }
+    pub fn flip_mut(&mut self, axis: usize) -> NArray<N, &mut [D::Elem]> {
+        self.as_view_mut().flipped(axis)
+    }
+    pub fn rot90_mut(&mut self, k: isize, axes: [usize; 2]) -> NArray<N, &mut [D::Elem]> {
+        self.as_view_mut().rotated(k, axes)
+    }
+    pub fn diagonal_mut(&mut self) -> NArray<1, &mut [D::Elem]> {
+        self.as_view_mut().into_diagonal()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Symmetry, narray::test::grid};
+
+    fn chars(grid: &str) -> NArray<2, Box<[char]>> {
+        NArray::from_grid_str(grid, |c| c).unwrap()
+    }
+
+    #[test]
+    fn symmetries() {
+        let a = chars("abc\ndef");
+        let expected = [
+            (Symmetry::Identity, chars("abc\ndef")),
+            (Symmetry::Rotate90, chars("da\neb\nfc")),
+            (Symmetry::Rotate180, chars("fed\ncba")),
+            (Symmetry::Rotate270, chars("cf\nbe\nad")),
+            (Symmetry::FlipX, chars("cba\nfed")),
+            (Symmetry::FlipY, chars("def\nabc")),
+            (Symmetry::Transpose, chars("ad\nbe\ncf")),
+            (Symmetry::AntiTranspose, chars("fc\neb\nda")),
+        ];
+        for (symmetry, image) in expected {
+            assert_eq!(symmetry.apply(&a), image, "{symmetry:?}");
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        let a = chars("abc\ndef");
+        for k in -4..8isize {
+            let expected = Symmetry::ROTATIONS[k.rem_euclid(4) as usize].apply(&a);
+            assert_eq!(a.rot90(k, [0, 1]).map(|c| *c), expected, "{k}");
+        }
+        // turning the second axis towards the first is counter-clockwise
+        assert_eq!(a.rot90(1, [1, 0]).map(|c| *c), chars("cf\nbe\nad"));
+        let p =
+            NArray::<3, Box<[i32]>>::from_fn([2, 3, 4], |[x, y, z]| (x + 10 * y + 100 * z) as i32);
+        let r = p.rot90(1, [0, 2]);
+        assert_eq!(r.size(), &[4, 3, 2]);
+        assert_eq!((r[[0, 0, 0]], r[[3, 2, 1]], r[[0, 0, 1]]), (300, 21, 301));
+    }
+
+    #[test]
+    fn views() {
+        let mut a = grid::<i32>([3, 2]);
+        assert_eq!(
+            a.transpose().map(|x| *x),
+            NArray::from_iter([2, 3], [0, 10, 1, 11, 2, 12]).unwrap()
+        );
+        assert_eq!(
+            a.flip(0).map(|x| *x),
+            NArray::from_iter([3, 2], [2, 1, 0, 12, 11, 10]).unwrap()
+        );
+        let p =
+            NArray::<3, Box<[i32]>>::from_fn([2, 3, 4], |[x, y, z]| (x + 10 * y + 100 * z) as i32);
+        let q = p.permute([2, 0, 1]);
+        assert_eq!(q.size(), &[4, 2, 3]);
+        assert_eq!(q[[3, 1, 2]], 321);
+        a.flip_mut(1)[[0, 0]] = 100;
+        assert_eq!(a[[0, 1]], 100);
+        let d = a.flip(0).into_diagonal();
+        assert_eq!((d.len(), d[[0]], d[[1]]), (2, 2, 11));
+    }
+}
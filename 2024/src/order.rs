@@ -0,0 +1,241 @@
+use std::{cmp::Reverse, collections::BinaryHeap, error::Error, fmt};
+
+/// Precedence relation over the items `0..len`, built from `(before, after)` rules.
+///
+/// The relation is not assumed to be transitive or acyclic, it only becomes
+/// a partial order once it is restricted to a suitable subset of items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precedence {
+    successors: Box<[Box<[usize]>]>,
+}
+impl Precedence {
+    pub fn new(len: usize, rules: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut successors = vec![Vec::new(); len];
+        for (before, after) in rules {
+            assert!(before < len && after < len, "item out of range");
+            successors[before].push(after);
+        }
+        Self {
+            successors: successors
+                .into_iter()
+                .map(|mut v| {
+                    v.sort_unstable();
+                    v.dedup();
+                    v.into_boxed_slice()
+                })
+                .collect(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+    /// Items that have to come after `item`.
+    pub fn successors(&self, item: usize) -> &[usize] {
+        &self.successors[item]
+    }
+    pub fn precedes(&self, before: usize, after: usize) -> bool {
+        self.successors[before].binary_search(&after).is_ok()
+    }
+    /// Only keep the rules where both items are part of `items`.
+    ///
+    /// Duplicates in `items` are ignored.
+    pub fn restrict(&self, items: &[usize]) -> Restriction<'_> {
+        let mut local = vec![None; self.len()].into_boxed_slice();
+        let mut subset = Vec::with_capacity(items.len());
+        for &item in items {
+            if local[item].is_none() {
+                local[item] = Some(subset.len());
+                subset.push(item);
+            }
+        }
+        Restriction {
+            relation: self,
+            items: subset.into_boxed_slice(),
+            local,
+        }
+    }
+    /// Checks that no rule between items of `sequence` is violated.
+    ///
+    /// Repeated items have to respect the rules at every occurrence.
+    pub fn respects(&self, sequence: &[usize]) -> bool {
+        let restriction = self.restrict(sequence);
+        let mut first = vec![usize::MAX; restriction.items.len()];
+        let mut last = vec![0; restriction.items.len()];
+        for (i, &item) in sequence.iter().enumerate() {
+            let index = restriction.index(item);
+            first[index] = first[index].min(i);
+            last[index] = i;
+        }
+        restriction.items.iter().all(|&before| {
+            restriction
+                .successors(before)
+                .all(|after| last[restriction.index(before)] < first[restriction.index(after)])
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Restriction<'a> {
+    relation: &'a Precedence,
+    items: Box<[usize]>,
+    local: Box<[Option<usize>]>,
+}
+impl Restriction<'_> {
+    pub fn items(&self) -> &[usize] {
+        &self.items
+    }
+    pub fn contains(&self, item: usize) -> bool {
+        self.local.get(item).is_some_and(Option::is_some)
+    }
+    pub fn precedes(&self, before: usize, after: usize) -> bool {
+        self.contains(before) && self.contains(after) && self.relation.precedes(before, after)
+    }
+    pub fn successors(&self, item: usize) -> impl Iterator<Item = usize> + '_ {
+        self.relation
+            .successors(item)
+            .iter()
+            .copied()
+            .filter(|after| self.contains(*after))
+    }
+    #[inline(always)]
+    fn index(&self, item: usize) -> usize {
+        // SAFETY: unwrap: only called on items of the subset
+        self.local[item].unwrap()
+    }
+    /// Orders all items of the subset so that every rule is respected.
+    ///
+    /// Unconstrained items keep their relative order from the subset.
+    pub fn toposort(&self) -> Result<Box<[usize]>, CycleError> {
+        let mut indegree = vec![0usize; self.items.len()];
+        for &item in &self.items {
+            for after in self.successors(item) {
+                indegree[self.index(after)] += 1;
+            }
+        }
+        // ready items by their position in the subset, earliest first
+        let mut ready = (0..self.items.len())
+            .filter(|i| indegree[*i] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(self.items.len());
+        while let Some(Reverse(i)) = ready.pop() {
+            let item = self.items[i];
+            order.push(item);
+            for after in self.successors(item) {
+                let degree = &mut indegree[self.index(after)];
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(Reverse(self.index(after)));
+                }
+            }
+        }
+        if order.len() == self.items.len() {
+            return Ok(order.into_boxed_slice());
+        }
+        // every item left over has a predecessor that is left over as well,
+        // so walking backwards has to run into a cycle eventually
+        let remaining = |item: usize| indegree[self.index(item)] > 0;
+        let mut visited = vec![None; self.items.len()];
+        let mut path = Vec::new();
+        // SAFETY: unwrap: not all items were ordered
+        let mut current = self
+            .items
+            .iter()
+            .copied()
+            .find(|item| remaining(*item))
+            .unwrap();
+        while visited[self.index(current)].is_none() {
+            visited[self.index(current)] = Some(path.len());
+            path.push(current);
+            // SAFETY: unwrap: remaining items have a remaining predecessor
+            current = self
+                .items
+                .iter()
+                .copied()
+                .find(|before| remaining(*before) && self.relation.precedes(*before, current))
+                .unwrap();
+        }
+        // SAFETY: unwrap: loop exits on a visited item
+        let mut cycle = path.split_off(visited[self.index(current)].unwrap());
+        cycle.reverse();
+        Err(CycleError(cycle.into_boxed_slice()))
+    }
+}
+
+/// Items forming a cycle, each one has to come before the next (and the last before the first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError(pub Box<[usize]>);
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle detected:")?;
+        for item in &self.0 {
+            write!(f, " {item} ->")?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, " {first}"),
+            None => Ok(()),
+        }
+    }
+}
+impl Error for CycleError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn relation() -> Precedence {
+        Precedence::new(5, [(0, 1), (1, 2), (0, 3), (3, 2)])
+    }
+
+    #[test]
+    fn respects() {
+        let relation = relation();
+        assert!(relation.respects(&[0, 1, 3, 2]));
+        assert!(relation.respects(&[4, 3, 2]));
+        assert!(!relation.respects(&[0, 2, 1]));
+        // repeated items are checked at every occurrence
+        assert!(relation.respects(&[0, 0, 4, 1, 4, 2]));
+        assert!(!relation.respects(&[0, 1, 0]));
+        assert!(!relation.respects(&[1, 2, 1]));
+    }
+
+    #[test]
+    fn toposort() {
+        let relation = relation();
+        let order = relation.restrict(&[2, 4, 3, 1, 0, 3]).toposort().unwrap();
+        assert_eq!(order.len(), 5);
+        assert!(relation.respects(&order));
+        // unconstrained items keep their order
+        let order = relation.restrict(&[4, 2, 1]).toposort().unwrap();
+        assert_eq!(&*order, [4, 1, 2]);
+        // an unconstrained item between two constrained ones stays in front of the later one
+        let relation = Precedence::new(3, [(0, 2)]);
+        let order = relation.restrict(&[0, 1, 2]).toposort().unwrap();
+        assert_eq!(&*order, [0, 1, 2]);
+        let order = relation.restrict(&[2, 1, 0]).toposort().unwrap();
+        assert_eq!(&*order, [1, 0, 2]);
+    }
+
+    #[test]
+    fn cycle() {
+        let relation = Precedence::new(4, [(0, 1), (1, 2), (2, 3), (3, 1)]);
+        let error = relation.restrict(&[0, 1, 2, 3]).toposort().unwrap_err();
+        let mut cycle = error.0.to_vec();
+        let start = cycle.iter().position(|&item| item == 1).unwrap();
+        cycle.rotate_left(start);
+        assert_eq!(cycle, [1, 2, 3]);
+        for (i, &before) in error.0.iter().enumerate() {
+            assert!(relation.precedes(before, error.0[(i + 1) % error.0.len()]));
+        }
+        // the cycle disappears when one of its items is left out
+        assert!(relation.restrict(&[0, 1, 2]).toposort().is_ok());
+        let error = Precedence::new(1, [(0, 0)])
+            .restrict(&[0])
+            .toposort()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "cycle detected: 0 -> 0");
+    }
+}
@@ -1,14 +1,19 @@
-use std::ops::{
-    Bound,
-    Range,
-    RangeBounds,
-    RangeFrom,
-    RangeFull,
-    RangeInclusive,
-    RangeTo,
-    RangeToInclusive,
+use std::{
+    iter::{Rev, StepBy},
+    ops::{
+        Bound,
+        Range,
+        RangeBounds,
+        RangeFrom,
+        RangeFull,
+        RangeInclusive,
+        RangeTo,
+        RangeToInclusive,
+    },
 };
 
+use num_traits::PrimInt;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RangeAny<T> {
     Range(Range<T>),
@@ -18,6 +23,8 @@ pub enum RangeAny<T> {
     RangeTo(RangeTo<T>),
     RangeToInclusive(RangeToInclusive<T>),
     Single(T),
+    /// Every `step.abs()`-th element of the inner range, starting from its last element if `step < 0`.
+    Step(Box<RangeAny<T>>, isize),
 }
 impl<T> RangeBounds<T> for RangeAny<T> {
     fn start_bound(&self) -> Bound<&T> {
@@ -29,6 +36,7 @@ impl<T> RangeBounds<T> for RangeAny<T> {
             RangeAny::RangeTo(range_to) => range_to.start_bound(),
             RangeAny::RangeToInclusive(range_to_inclusive) => range_to_inclusive.start_bound(),
             RangeAny::Single(x) => Bound::Included(x),
+            RangeAny::Step(range, _) => range.start_bound(),
         }
     }
     fn end_bound(&self) -> std::ops::Bound<&T> {
@@ -40,27 +48,39 @@ impl<T> RangeBounds<T> for RangeAny<T> {
             RangeAny::RangeTo(range_to) => range_to.end_bound(),
             RangeAny::RangeToInclusive(range_to_inclusive) => range_to_inclusive.end_bound(),
             RangeAny::Single(x) => Bound::Included(x),
+            RangeAny::Step(range, _) => range.end_bound(),
         }
     }
 }
-// TODO: generalize this
-impl RangeAny<usize> {
-    pub const fn start(&self) -> Option<usize> {
+impl<T> RangeAny<T> {
+    /// The same elements in reverse order.
+    pub fn rev(self) -> Self {
+        Self::Step(Box::new(self), -1)
+    }
+    /// Every `step.abs()`-th element, going backwards if `step < 0`.
+    pub fn step_by(self, step: isize) -> Self {
+        Self::Step(Box::new(self), step)
+    }
+}
+impl<T: PrimInt> RangeAny<T> {
+    pub fn start(&self) -> Option<T> {
         match self {
             RangeAny::Range(range) => Some(range.start),
             RangeAny::RangeFrom(range_from) => Some(range_from.start),
             RangeAny::RangeInclusive(range_inclusive) => Some(*range_inclusive.start()),
             RangeAny::Single(x) => Some(*x),
+            RangeAny::Step(range, _) => range.start(),
             _ => None,
         }
     }
-    pub const fn end(&self) -> Option<usize> {
+    pub fn end(&self) -> Option<T> {
         match self {
-            RangeAny::Range(range) => range.end.checked_sub(1),
+            RangeAny::Range(range) => range.end.checked_sub(&T::one()),
             RangeAny::RangeInclusive(range_inclusive) => Some(*range_inclusive.end()),
-            RangeAny::RangeTo(range_to) => range_to.end.checked_sub(1),
+            RangeAny::RangeTo(range_to) => range_to.end.checked_sub(&T::one()),
             RangeAny::RangeToInclusive(range_to_inclusive) => Some(range_to_inclusive.end),
             RangeAny::Single(x) => Some(*x),
+            RangeAny::Step(range, _) => range.end(),
             _ => None,
         }
     }
@@ -84,9 +104,63 @@ impl<T> From<RangeFull> for RangeAny<T> {
         Self::RangeFull(value)
     }
 }
-// TODO: generalize this
-impl From<usize> for RangeAny<usize> {
-    fn from(value: usize) -> Self {
-        Self::Single(value)
+macro_rules! impl_from_single {
+    ($($type: ident),+) => {
+        $(
+            impl From<$type> for RangeAny<$type> {
+                fn from(value: $type) -> Self {
+                    Self::Single(value)
+                }
+            }
+        )+
+    };
+}
+impl_from_single!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// `rev` and `step_by` for ranges that are not iterators themselves, e.g. `(..).rev()`.
+pub trait RangeStep<T>: Into<RangeAny<T>> {
+    fn rev(self) -> RangeAny<T> {
+        self.into().rev()
+    }
+    fn step_by(self, step: isize) -> RangeAny<T> {
+        self.into().step_by(step)
+    }
+}
+impl<T> RangeStep<T> for RangeFull {}
+impl<T> RangeStep<T> for RangeTo<T> {}
+impl<T> RangeStep<T> for RangeToInclusive<T> {}
+/// Stepped range with the elements of `iter`, which has to be an arithmetic progression.
+fn from_progression(mut iter: impl Iterator<Item = usize>) -> RangeAny<usize> {
+    let Some(first) = iter.next() else {
+        return RangeAny::from(0..0);
+    };
+    let Some(second) = iter.next() else {
+        return RangeAny::from(first..=first);
+    };
+    let last = iter.last().unwrap_or(second);
+    let range = RangeAny::from(first.min(last)..=first.max(last));
+    match second as isize - first as isize {
+        1 => range,
+        step => range.step_by(step),
     }
 }
+macro_rules! impl_from_progression {
+    ($($type: ty),+) => {
+        $(
+            impl From<$type> for RangeAny<usize> {
+                fn from(value: $type) -> Self {
+                    from_progression(value)
+                }
+            }
+        )+
+    };
+}
+impl_from_progression!(
+    Rev<Range<usize>>,
+    Rev<RangeInclusive<usize>>,
+    StepBy<Range<usize>>,
+    StepBy<RangeInclusive<usize>>,
+    Rev<StepBy<Range<usize>>>
+);